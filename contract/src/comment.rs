@@ -1,32 +1,170 @@
+use std::collections::HashMap;
+
 use near_sdk::{serde::{Serialize, Deserialize}, AccountId};
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 
-use crate::CommentId;
+use crate::{CommentId, PostId, VoteStatus, legacy::CommentV1, reaction::Reaction, tombstone::Tombstone};
 
 #[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
 #[serde(crate = "near_sdk::serde")]
 pub struct Comment {
     comment_id: usize,
+    post_id: usize,
+    parent_id: Option<usize>,
+    depth: usize,
+    children: Vec<usize>,
     body: String,
     author: AccountId,
     created_at: u64,
+    tombstone: Option<Tombstone>,
+    /// Hidden after being reported too many times, until a moderator restores it.
+    hidden: bool,
+
+    /// Voters and reactors are kept in `Blog::comment_votes` and `Blog::comment_reactions`.
+    upvote_count: u64,
+    downvote_count: u64,
+    reaction_counts: HashMap<Reaction, u64>,
 }
 
 impl Comment {
-    pub fn new(comment_id: usize, body: String, author: AccountId, created_at: u64) -> Self {
+    pub fn new(comment_id: usize, post_id: usize, parent_id: Option<usize>, depth: usize, body: String, author: AccountId, created_at: u64) -> Self {
         Self {
             comment_id,
+            post_id,
+            parent_id,
+            depth,
+            children: Vec::new(),
             body,
             author,
             created_at,
+            tombstone: None,
+            hidden: false,
+
+            upvote_count: 0,
+            downvote_count: 0,
+            reaction_counts: HashMap::new(),
         }
     }
 
+    /// Version 1 comments were not linked to their post and had no replies.
+    pub fn from_v1(comment: CommentV1, post_id: PostId) -> Self {
+        Self::new(comment.comment_id, post_id, None, 0, comment.body, comment.author, comment.created_at)
+    }
+
     pub fn get_comment_id(&self) -> CommentId {
         self.comment_id
     }
 
+    pub fn get_post_id(&self) -> PostId {
+        self.post_id
+    }
+
+    pub fn get_parent_id(&self) -> Option<CommentId> {
+        self.parent_id
+    }
+
+    pub fn get_depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Replies are kept sorted by id, which is the order they were written in.
+    pub fn add_child(&mut self, comment_id: usize) {
+        if let Err(index) = self.children.binary_search(&comment_id) {
+            self.children.insert(index, comment_id);
+        }
+    }
+
+    pub fn remove_child(&mut self, comment_id: usize) {
+        if let Ok(index) = self.children.binary_search(&comment_id) {
+            self.children.remove(index);
+        }
+    }
+
+    pub fn get_children(&self) -> Vec<usize> {
+        self.children.clone()
+    }
+
     pub fn get_body(&self) -> String {
         self.body.clone()
     }
-}
\ No newline at end of file
+
+    pub fn get_author(&self) -> AccountId {
+        self.author.clone()
+    }
+
+    /// Keeps the vote counters in sync when an account changes its vote from `previous` to `next`.
+    pub fn update_vote_counts(&mut self, previous: &VoteStatus, next: &VoteStatus) {
+        match previous {
+            VoteStatus::Upvoted => self.upvote_count -= 1,
+            VoteStatus::Downvoted => self.downvote_count -= 1,
+            VoteStatus::None => {},
+        }
+
+        match next {
+            VoteStatus::Upvoted => self.upvote_count += 1,
+            VoteStatus::Downvoted => self.downvote_count += 1,
+            VoteStatus::None => {},
+        }
+    }
+
+    /// Keeps the reaction counters in sync when an account changes its reaction from `previous` to `next`.
+    pub fn update_reaction_counts(&mut self, previous: Option<Reaction>, next: Option<Reaction>) {
+        if let Some(previous) = previous {
+            let count = self.reaction_counts.get(&previous).copied().unwrap_or(0);
+
+            if count <= 1 {
+                self.reaction_counts.remove(&previous);
+            } else {
+                self.reaction_counts.insert(previous, count - 1);
+            }
+        }
+
+        if let Some(next) = next {
+            *self.reaction_counts.entry(next).or_insert(0) += 1;
+        }
+    }
+
+    pub fn get_upvote_count(&self) -> u64 {
+        self.upvote_count
+    }
+
+    pub fn get_downvote_count(&self) -> u64 {
+        self.downvote_count
+    }
+
+    pub fn get_reaction_count(&self, reaction: Reaction) -> u64 {
+        self.reaction_counts.get(&reaction).copied().unwrap_or(0)
+    }
+
+    /// Drops the body but keeps the comment in its thread, so its replies stay reachable.
+    pub fn delete(&mut self, tombstone: Tombstone) {
+        self.body = String::new();
+        self.tombstone = Some(tombstone);
+    }
+
+    pub fn is_deleted(&self) -> bool {
+        self.tombstone.is_some()
+    }
+
+    pub fn get_tombstone(&self) -> Option<Tombstone> {
+        self.tombstone.clone()
+    }
+
+    pub fn set_hidden(&mut self, hidden: bool) {
+        self.hidden = hidden;
+    }
+
+    pub fn is_hidden(&self) -> bool {
+        self.hidden
+    }
+
+    /// Whether the comment shows up in listings.
+    pub fn is_listed(&self) -> bool {
+        !self.is_deleted() && !self.hidden
+    }
+
+    /// Blanks the body of a hidden comment shown as a placeholder in its thread.
+    pub fn redact(&mut self) {
+        self.body = String::new();
+    }
+}
@@ -0,0 +1,70 @@
+use near_sdk::serde::{Serialize, Deserialize};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+/// Deepest replies a blog can allow, every level is read when a thread is walked.
+const MAX_COMMENT_DEPTH: usize = 10;
+
+/// Content policy of the blog, set by the owner or an admin. Lengths are in bytes.
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Config {
+    pub min_title_length: usize,
+    pub max_title_length: usize,
+    pub min_body_length: usize,
+    pub max_body_length: usize,
+    pub min_comment_length: usize,
+    pub max_comment_length: usize,
+    pub min_tag_length: usize,
+    pub max_tag_length: usize,
+    pub max_tags_per_post: usize,
+    /// Replies can be nested this many levels below a top-level comment.
+    pub max_comment_depth: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            min_title_length: 1,
+            max_title_length: 200,
+            min_body_length: 1,
+            max_body_length: 50_000,
+            min_comment_length: 10,
+            max_comment_length: 5_000,
+            min_tag_length: 1,
+            max_tag_length: 32,
+            max_tags_per_post: 5,
+            max_comment_depth: 5,
+        }
+    }
+}
+
+impl Config {
+    pub fn assert_valid(&self) {
+        assert!(self.min_title_length > 0 && self.min_title_length <= self.max_title_length, "Invalid title length bounds");
+        assert!(self.min_body_length > 0 && self.min_body_length <= self.max_body_length, "Invalid body length bounds");
+        assert!(self.min_comment_length > 0 && self.min_comment_length <= self.max_comment_length, "Invalid comment length bounds");
+        assert!(self.min_tag_length > 0 && self.min_tag_length <= self.max_tag_length, "Invalid tag length bounds");
+        assert!(self.max_tags_per_post > 0, "Max tags per post must be greater than 0");
+        // a depth of 0 would turn off replies, which the comment threads are built on
+        assert!(self.max_comment_depth > 0, "Max comment depth must be greater than 0");
+        assert!(self.max_comment_depth <= MAX_COMMENT_DEPTH, "Max comment depth must be at most {}", MAX_COMMENT_DEPTH);
+    }
+
+    pub fn assert_valid_title(&self, title: &str) {
+        assert_length("Title", title, self.min_title_length, self.max_title_length);
+    }
+
+    /// Only on-chain bodies are checked, off-chain ones can be of any length.
+    pub fn assert_valid_body(&self, body: &str) {
+        assert_length("Body", body, self.min_body_length, self.max_body_length);
+    }
+
+    pub fn assert_valid_comment(&self, body: &str) {
+        assert_length("Comment", body, self.min_comment_length, self.max_comment_length);
+    }
+}
+
+fn assert_length(name: &str, text: &str, min: usize, max: usize) {
+    assert!(text.len() >= min, "{} must be at least {} characters long", name, min);
+    assert!(text.len() <= max, "{} must be at most {} characters long", name, max);
+}
@@ -0,0 +1,57 @@
+use near_sdk::serde::{Serialize, Deserialize};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+pub const MAX_CONTENT_URI_LENGTH: usize = 512;
+
+/// A post body kept off-chain, e.g. `ipfs://<cid>`, `ar://<id>` or an https URL.
+/// Clients check the fetched bytes against `sha256` and `length` before showing them.
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct OffChainContent {
+    uri: String,
+    /// Hex encoded SHA-256 of the body, lowercase.
+    sha256: String,
+    /// Size of the body in bytes.
+    length: u64,
+}
+
+impl OffChainContent {
+    pub fn new(uri: String, sha256: String, length: u64) -> Self {
+        let content = Self { uri, sha256, length };
+        content.assert_valid();
+
+        content
+    }
+
+    pub fn assert_valid(&self) {
+        assert!(!self.uri.is_empty(), "Content URI can't be empty");
+        assert!(self.uri.len() <= MAX_CONTENT_URI_LENGTH, "Content URI must be at most {} characters long", MAX_CONTENT_URI_LENGTH);
+        assert!(!self.uri.chars().any(char::is_whitespace), "Content URI can't contain whitespace");
+        assert!(
+            self.sha256.len() == 64 && self.sha256.chars().all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c)),
+            "Content hash must be a lowercase hex encoded SHA-256"
+        );
+        assert!(self.length > 0, "Content length must be greater than 0");
+    }
+
+    pub fn get_uri(&self) -> String {
+        self.uri.clone()
+    }
+
+    pub fn get_sha256(&self) -> String {
+        self.sha256.clone()
+    }
+
+    pub fn get_length(&self) -> u64 {
+        self.length
+    }
+}
+
+/// Where the body of a post lives. Off-chain posts keep an empty `body`.
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum PostContent {
+    OnChain,
+    OffChain(OffChainContent),
+}
@@ -2,22 +2,50 @@ use near_sdk::AccountId;
 use near_sdk::serde::{Serialize, Deserialize};
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 
+use crate::{PostId, legacy::DonationLogV1};
+
 #[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
 #[serde(crate = "near_sdk::serde")]
 pub struct DonationLog {
     donation_id: usize,
     amount: u128,
+    /// Fungible token contract the donation was made in, `None` for NEAR.
+    token_id: Option<AccountId>,
     donor: AccountId,
     created_at: u64,
     message: String,
     post_id: usize,
 }
 
+/// The `msg` attached to `ft_transfer_call` when donating fungible tokens to a post.
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DonationMessage {
+    pub post_id: PostId,
+    #[serde(default)]
+    pub message: String,
+}
+
+impl From<DonationLogV1> for DonationLog {
+    fn from(donation_log: DonationLogV1) -> Self {
+        Self::new(
+            donation_log.donation_id,
+            donation_log.amount,
+            None,
+            donation_log.donor,
+            donation_log.created_at,
+            donation_log.message,
+            donation_log.post_id,
+        )
+    }
+}
+
 impl DonationLog {
-    pub fn new(donation_id: usize, amount: u128, donor: AccountId, created_at: u64, message: String, post_id: usize) -> Self {
+    pub fn new(donation_id: usize, amount: u128, token_id: Option<AccountId>, donor: AccountId, created_at: u64, message: String, post_id: usize) -> Self {
         Self {
             donation_id,
             amount,
+            token_id,
             donor,
             created_at,
             message,
@@ -28,4 +56,8 @@ impl DonationLog {
     pub fn get_amount(&self) -> u128 {
         self.amount
     }
-}
\ No newline at end of file
+
+    pub fn get_token_id(&self) -> Option<AccountId> {
+        self.token_id.clone()
+    }
+}
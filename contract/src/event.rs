@@ -0,0 +1,75 @@
+use near_sdk::{env, serde_json, AccountId};
+use near_sdk::json_types::U128;
+use near_sdk::serde::Serialize;
+
+use crate::{config::Config, pause::Feature, CommentId, PostId, VoteStatus, reaction::Reaction, report::ReportTarget, rate_limit::{RateLimit, RateLimitedAction}, role::Role, sanction::SanctionKind};
+
+/// Events are logged as `EVENT_JSON:{"standard": .., "version": .., "event": .., "data": ..}` (NEP-297).
+pub const EVENT_STANDARD: &str = "decentrablog";
+pub const EVENT_STANDARD_VERSION: &str = "1.0.0";
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(tag = "event", content = "data", rename_all = "snake_case")]
+pub enum BlogEvent {
+    PostCreated { post_id: PostId, author: AccountId, title: String },
+    PostEdited { post_id: PostId, editor: AccountId, revision_id: usize },
+    PostDeleted { post_id: PostId, deleted_by: AccountId, reason: Option<String> },
+    PostUnpublished { post_id: PostId, author: AccountId },
+    PostPublished { post_id: PostId, author: AccountId, publish_at: u64 },
+    CommentCreated { comment_id: CommentId, post_id: PostId, parent_id: Option<CommentId>, author: AccountId },
+    CommentDeleted { comment_id: CommentId, post_id: PostId, deleted_by: AccountId, reason: Option<String> },
+    PostVoted { post_id: PostId, voter: AccountId, status: VoteStatus },
+    PostReacted { post_id: PostId, account_id: AccountId, reaction: Option<String> },
+    PostReactionKindsUpdated { kinds: Vec<String> },
+    CommentVoted { comment_id: CommentId, voter: AccountId, status: VoteStatus },
+    CommentReacted { comment_id: CommentId, account_id: AccountId, reaction: Option<Reaction> },
+    DonationReceived { post_id: PostId, donation_id: usize, donor: AccountId, token_id: Option<AccountId>, amount: U128 },
+    DonationRefunded { post_id: PostId, donor: AccountId, token_id: Option<AccountId>, amount: U128 },
+    RoleGranted { account_id: AccountId, role: Role, granted_by: AccountId },
+    RoleRevoked { account_id: AccountId, role: Role, revoked_by: AccountId },
+    OwnerProposed { owner: AccountId, proposed_owner: AccountId },
+    OwnerProposalCancelled { owner: AccountId, proposed_owner: AccountId },
+    OwnershipTransferred { previous_owner: AccountId, new_owner: AccountId },
+    StorageDeposited { account_id: AccountId, amount: U128 },
+    StorageWithdrawn { account_id: AccountId, amount: U128 },
+    StorageUnregistered { account_id: AccountId, refund: U128 },
+    ContentReported { target: ReportTarget, reporter: AccountId, report_count: u64 },
+    ContentHidden { target: ReportTarget, report_count: u64 },
+    ContentRestored { target: ReportTarget, moderator: AccountId },
+    ReportResolved { target: ReportTarget, moderator: AccountId },
+    ReportDismissed { target: ReportTarget, moderator: AccountId },
+    ReportThresholdUpdated { threshold: u64 },
+    AccountSanctioned { account_id: AccountId, kind: SanctionKind, reason: String, expires_at: Option<u64>, issued_by: AccountId },
+    SanctionLifted { account_id: AccountId, lifted_by: AccountId },
+    RateLimitUpdated { action: RateLimitedAction, limit: Option<RateLimit> },
+    StateMigrated { state_version: u32 },
+    ConfigUpdated { config: Config, updated_by: AccountId },
+    FeaturePaused { feature: Feature, paused_by: AccountId },
+    FeatureUnpaused { feature: Feature, unpaused_by: AccountId },
+    ProfileUpdated { account_id: AccountId },
+    ProfileRemoved { account_id: AccountId },
+    Followed { follower: AccountId, account_id: AccountId },
+    Unfollowed { follower: AccountId, account_id: AccountId },
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+struct EventLog<'a> {
+    standard: &'static str,
+    version: &'static str,
+    #[serde(flatten)]
+    event: &'a BlogEvent,
+}
+
+impl BlogEvent {
+    pub fn emit(&self) {
+        let log = EventLog {
+            standard: EVENT_STANDARD,
+            version: EVENT_STANDARD_VERSION,
+            event: self,
+        };
+
+        env::log(format!("EVENT_JSON:{}", serde_json::to_string(&log).unwrap()).as_bytes());
+    }
+}
@@ -0,0 +1,25 @@
+use near_sdk::serde::{Serialize, Deserialize};
+
+use near_sdk::AccountId;
+
+use crate::{PostId, post::Post};
+
+/// Followed accounts `get_feed` reads in a single call.
+pub const MAX_FEED_AUTHORS: usize = 50;
+
+/// Where a feed continues. The followed accounts are read by account id `MAX_FEED_AUTHORS` at a time,
+/// starting after `after_author`, and within them the posts below `from_post_id`, newest first.
+#[derive(Serialize, Deserialize, Default, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FeedCursor {
+    pub after_author: Option<AccountId>,
+    pub from_post_id: Option<PostId>,
+}
+
+/// A page of a feed, `next` is `None` once every followed account was read.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FeedPage {
+    pub posts: Vec<Post>,
+    pub next: Option<FeedCursor>,
+}
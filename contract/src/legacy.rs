@@ -0,0 +1,106 @@
+//! State layouts of released contract versions, read by `Blog::migrate` and `Blog::migrate_batch`.
+//!
+//! The version of a state is stored under `STATE_VERSION_KEY`, apart from the layout it describes,
+//! a state without that key is version 1. When a release changes the Borsh layout of `Blog` or of a
+//! stored type, bump `STATE_VERSION`, keep the released layout here and convert it in `migrate`.
+
+use std::collections::HashSet;
+
+use near_sdk::AccountId;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::UnorderedMap;
+
+use crate::{CommentId, PostId};
+
+/// Version 1: the layout deployed before state versioning was introduced.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct BlogV1 {
+    pub owner: AccountId,
+    pub user_posts: UnorderedMap<AccountId, Vec<usize>>,
+    pub posts: UnorderedMap<PostId, PostV1>,
+    pub comments: UnorderedMap<CommentId, CommentV1>,
+
+    pub next_post_id: usize,
+    pub next_comment_id: usize,
+    pub next_donation_id: usize,
+}
+
+/// The version 1 collections `Blog::migrate_batch` has not moved yet.
+/// They keep their prefixes, the migrated state writes its own collections under new ones.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct LegacyV1 {
+    pub user_posts: UnorderedMap<AccountId, Vec<usize>>,
+    pub posts: UnorderedMap<PostId, PostV1>,
+    pub comments: UnorderedMap<CommentId, CommentV1>,
+    /// A post put back when a batch ran out, with how many of its comments and votes were moved already.
+    pub post_progress: Option<(PostId, usize)>,
+}
+
+impl LegacyV1 {
+    pub fn pop_post(&mut self) -> Option<(PostId, PostV1)> {
+        pop(&mut self.posts)
+    }
+
+    /// Puts a post back as the next one to pop, `moved` of its comments and votes are not moved again.
+    pub fn push_post(&mut self, post_id: PostId, post: &PostV1, moved: usize) {
+        self.posts.insert(&post_id, post);
+        self.post_progress = Some((post_id, moved));
+    }
+
+    pub fn pop_comment(&mut self) -> Option<(CommentId, CommentV1)> {
+        pop(&mut self.comments)
+    }
+
+    pub fn pop_user_posts(&mut self) -> Option<(AccountId, Vec<usize>)> {
+        pop(&mut self.user_posts)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.posts.is_empty() && self.comments.is_empty() && self.user_posts.is_empty()
+    }
+}
+
+/// Removes the last entry of a map, which `UnorderedMap` does without moving any other entry.
+fn pop<K: BorshSerialize + BorshDeserialize, V: BorshSerialize + BorshDeserialize>(map: &mut UnorderedMap<K, V>) -> Option<(K, V)> {
+    if map.is_empty() {
+        return None;
+    }
+
+    let key = map.keys_as_vector().get(map.len() - 1).unwrap();
+    let value = map.remove(&key).unwrap();
+
+    Some((key, value))
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct PostV1 {
+    pub post_id: usize,
+    pub title: String,
+    pub body: String,
+    pub author: AccountId,
+    pub created_at: u64,
+    pub comments: Vec<usize>,
+
+    pub upvotes: HashSet<AccountId>,
+    pub downvotes: HashSet<AccountId>,
+
+    pub donation_logs: Vec<DonationLogV1>,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct CommentV1 {
+    pub comment_id: usize,
+    pub body: String,
+    pub author: AccountId,
+    pub created_at: u64,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct DonationLogV1 {
+    pub donation_id: usize,
+    pub amount: u128,
+    pub donor: AccountId,
+    pub created_at: u64,
+    pub message: String,
+    pub post_id: usize,
+}
@@ -6,39 +6,181 @@
  *
  */
 
+// the generated `ext_contract` functions take the receiver, deposit and gas on top of the callback arguments
+#![allow(clippy::too_many_arguments)]
+
 // To conserve gas, efficient serialization is achieved through Borsh (http://borsh.io/)
+use std::cmp::Reverse;
+use std::collections::HashMap;
 use std::convert::TryInto;
 use comment::Comment;
-use donation::DonationLog;
+use donation::{DonationLog, DonationMessage};
+use event::BlogEvent;
+use feed::{FeedCursor, FeedPage, MAX_FEED_AUTHORS};
+use legacy::{BlogV1, LegacyV1, PostV1};
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::{env, near_bindgen, setup_alloc, AccountId, Promise};
-use near_sdk::collections::{UnorderedMap};
+use near_sdk::{env, ext_contract, near_bindgen, serde_json, setup_alloc, AccountId, Balance, Gas, Promise, PromiseOrValue, PromiseResult, StorageUsage};
+use near_sdk::collections::{LookupMap, TreeMap, UnorderedMap};
+use near_sdk::json_types::{U128, ValidAccountId};
 use near_sdk::serde::{Serialize, Deserialize};
-use post::Post;
+use post::{Post, PostStatus};
+use profile::{Profile, MAX_PROFILES_PER_CALL};
+use ranking::Ranking;
+use report::{Report, ReportCase, ReportTarget, DEFAULT_REPORT_THRESHOLD};
+use reaction::{assert_valid_post_reaction_kinds, default_post_reaction_kinds, Reaction};
+use revision::{Revision, MAX_REVISIONS_PER_CALL, MAX_REVISIONS_PER_POST};
+use rate_limit::{Quota, RateLimit, RateLimitedAction, RateLimits};
+use pause::Feature;
+use role::Role;
+use config::Config;
+use content::{OffChainContent, PostContent};
+use sanction::{Sanction, SanctionKind};
+use tag::normalize_tags;
+use tombstone::Tombstone;
+use storage::{StorageAccount, StorageBalance, StorageBalanceBounds, STORAGE_REGISTRATION_BYTES};
 
 setup_alloc!();
 
 type PostId = usize;
 type CommentId = usize;
 
+/// An account can follow this many accounts, `get_feed` reads their posts `MAX_FEED_AUTHORS` accounts at a time.
+const MAX_FOLLOWING: usize = 500;
+
+/// Most posts a single batch view resolves.
+const MAX_POSTS_PER_CALL: usize = 100;
+
+/// Most tags a single view lists.
+const MAX_TAGS_PER_CALL: usize = 100;
+
+/// Most followers or followed accounts a single view lists.
+const MAX_ACCOUNTS_PER_CALL: usize = 100;
+
+/// Most comments of a thread a single view lists.
+const MAX_COMMENTS_PER_CALL: usize = 100;
+
+/// Most posts of a window `get_ranked_posts` ranks, the latest ones.
+const MAX_RANKED_WINDOW_POSTS: usize = 500;
+
+/// Deepest post the deprecated `get_paging_posts` reaches, the rest are listed by `get_posts_by_cursor`.
+const MAX_PAGED_POSTS: usize = 1000;
+
+/// Version of the layout `Blog` is stored with, see `legacy` for the previous ones.
+const STATE_VERSION: u32 = 2;
+
+/// Holds the version of the stored layout, written by `migrate` and by the first storage registration of a new blog.
+const STATE_VERSION_KEY: &[u8] = b"state_version";
+
+/// Most version 1 entries a single `migrate_batch` call moves.
+const MAX_MIGRATION_BATCH: usize = 100;
+
+const GAS_FOR_DONATION_CALLBACK: Gas = 10_000_000_000_000;
+const GAS_FOR_FT_TRANSFER: Gas = 10_000_000_000_000;
+
 mod comment;
+mod config;
+mod content;
 mod post;
+mod profile;
+mod ranking;
+mod rate_limit;
+mod report;
+mod reaction;
 mod donation;
+mod event;
+mod feed;
+mod pause;
+mod legacy;
+mod revision;
+mod role;
+mod sanction;
+mod storage;
+mod tag;
+mod tombstone;
+
+#[ext_contract(ext_self)]
+pub trait ExtSelf {
+    fn on_donation_transferred(&mut self, post_id: usize, donor: AccountId, amount: U128, message: String) -> bool;
+    fn on_ft_donation_transferred(&mut self, post_id: usize, donor: AccountId, token_id: AccountId, amount: U128, message: String) -> U128;
+}
+
+#[ext_contract(ext_ft)]
+pub trait FungibleToken {
+    fn ft_transfer(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>);
+}
 
 #[near_bindgen]
 #[derive(BorshDeserialize, BorshSerialize)]
 pub struct Blog {
     owner: AccountId,
+    proposed_owner: Option<AccountId>,
     user_posts: UnorderedMap<AccountId, Vec<usize>>,
     posts: UnorderedMap<PostId, Post>,
+    /// Ids of the published and scheduled posts ordered by creation, mapped to the time they become visible.
+    post_index: TreeMap<PostId, u64>,
+    /// The posts of `post_index` ordered by rank, mapped to the time they become visible.
+    top_index: TreeMap<(i64, PostId), u64>,
+    hot_index: TreeMap<(i64, PostId), u64>,
+    controversial_index: TreeMap<(i64, PostId), u64>,
+    /// The posts of `post_index` ordered by the time they become visible, mapped to their upvotes and downvotes.
+    time_index: TreeMap<(u64, PostId), (u64, u64)>,
+    /// The posts of `post_index` under each of their tags, mapped to the time they become visible.
+    tag_posts: TreeMap<(String, PostId), u64>,
+    /// Number of posts of every tag in `tag_posts`.
+    tag_counts: LookupMap<String, u64>,
+    /// Tags keyed by `u64::MAX - count`, so the most used come first and ties are ordered by name.
+    tag_ranking: TreeMap<(u64, String), u64>,
     comments: UnorderedMap<CommentId, Comment>,
+    /// The last `MAX_REVISIONS_PER_POST` revisions of every post, keyed by post and revision id.
+    revisions: LookupMap<(PostId, usize), Revision>,
+    /// Number of revisions ever made of every edited post, the id of its next revision.
+    revision_counts: LookupMap<PostId, usize>,
+    roles: UnorderedMap<AccountId, Role>,
+    post_votes: LookupMap<(PostId, AccountId), VoteStatus>,
+    post_reactions: LookupMap<(PostId, AccountId), String>,
+    /// Reactions accounts can leave on posts, set by the owner.
+    post_reaction_kinds: Vec<String>,
+    comment_votes: LookupMap<(CommentId, AccountId), VoteStatus>,
+    /// Open report cases, the moderation queue.
+    report_cases: UnorderedMap<ReportTarget, ReportCase>,
+    /// The targets of `report_cases` by the time their case was opened.
+    report_queue: TreeMap<(u64, ReportTarget), ()>,
+    /// Reports needed to hide a post or comment until it is reviewed.
+    report_threshold: u64,
+    sanctions: UnorderedMap<AccountId, Sanction>,
+    rate_limits: RateLimits,
+    /// Timestamps of the rate limited actions of each account still inside their window.
+    action_logs: LookupMap<(AccountId, RateLimitedAction), Vec<u64>>,
+    comment_reactions: LookupMap<(CommentId, AccountId), Reaction>,
+    /// Top-level comments of every post that start a thread, see `attach_comment`.
+    comment_threads: TreeMap<(PostId, CommentId), ()>,
+    storage_accounts: LookupMap<AccountId, StorageAccount>,
+    /// Bytes of content left by accounts that unregistered with `force`, paid for by the deposit they left behind.
+    orphaned_bytes: LookupMap<AccountId, StorageUsage>,
+    profiles: LookupMap<AccountId, Profile>,
+    /// Follows keyed by `(follower, followed)` and by `(followed, follower)`, mapped to the time they were made.
+    following: TreeMap<(AccountId, AccountId), u64>,
+    followers: TreeMap<(AccountId, AccountId), u64>,
+    /// Number of followers and of followed accounts of every account with a follow.
+    follow_counts: LookupMap<AccountId, (u64, u64)>,
 
     next_post_id: usize,
     next_comment_id: usize,
     next_donation_id: usize,
+    config: Config,
+    paused_features: Vec<Feature>,
+    /// Version 1 collections left to move, until `migrate_batch` is done.
+    legacy: Option<LegacyV1>,
 }
 
-#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone, Copy, PartialEq, Debug)]
 #[serde(crate = "near_sdk::serde")]
 pub enum VoteStatus {
     Upvoted,
@@ -50,26 +192,204 @@ impl Default for Blog {
   fn default() -> Self {
     Self {
       owner: env::signer_account_id(),
+      proposed_owner: None,
       user_posts: UnorderedMap::new(b"user_posts".to_vec()),
       posts: UnorderedMap::new(b"posts".to_vec()),
+      post_index: TreeMap::new(b"post_index".to_vec()),
+      top_index: TreeMap::new(b"top_index".to_vec()),
+      hot_index: TreeMap::new(b"hot_index".to_vec()),
+      controversial_index: TreeMap::new(b"controversial_index".to_vec()),
+      time_index: TreeMap::new(b"time_index".to_vec()),
+      tag_posts: TreeMap::new(b"tag_posts".to_vec()),
+      tag_counts: LookupMap::new(b"tag_counts".to_vec()),
+      tag_ranking: TreeMap::new(b"tag_ranking".to_vec()),
       comments: UnorderedMap::new(b"comments".to_vec()),
+      revisions: LookupMap::new(b"revisions".to_vec()),
+      revision_counts: LookupMap::new(b"revision_counts".to_vec()),
+      roles: UnorderedMap::new(b"roles".to_vec()),
+      post_votes: LookupMap::new(b"post_votes".to_vec()),
+      post_reactions: LookupMap::new(b"post_reactions".to_vec()),
+      post_reaction_kinds: default_post_reaction_kinds(),
+      comment_votes: LookupMap::new(b"comment_votes".to_vec()),
+      report_cases: UnorderedMap::new(b"report_cases".to_vec()),
+      report_queue: TreeMap::new(b"report_queue".to_vec()),
+      report_threshold: DEFAULT_REPORT_THRESHOLD,
+      sanctions: UnorderedMap::new(b"sanctions".to_vec()),
+      rate_limits: RateLimits::default(),
+      action_logs: LookupMap::new(b"action_logs".to_vec()),
+      comment_reactions: LookupMap::new(b"comment_reactions".to_vec()),
+      comment_threads: TreeMap::new(b"comment_threads".to_vec()),
+      storage_accounts: LookupMap::new(b"storage_accounts".to_vec()),
+      orphaned_bytes: LookupMap::new(b"orphaned_bytes".to_vec()),
+      profiles: LookupMap::new(b"profiles".to_vec()),
+      following: TreeMap::new(b"following".to_vec()),
+      followers: TreeMap::new(b"followers".to_vec()),
+      follow_counts: LookupMap::new(b"follow_counts".to_vec()),
 
       next_post_id: 0,
       next_comment_id: 0,
       next_donation_id: 0,
+      config: Config::default(),
+      paused_features: Vec::new(),
+      legacy: None,
     }
   }
 }
 
 #[near_bindgen]
 impl Blog {
-    pub fn create_post(&mut self, title: String, body: String) -> usize {
+    /// Upgrades the state written by the previous contract version, call it right after deploying new code.
+    /// Only the contract fields are converted here, the content is moved by `migrate_batch`.
+    #[init(ignore_state)]
+    pub fn migrate() -> Self {
+        let version = match env::storage_read(STATE_VERSION_KEY) {
+            Some(bytes) => u32::try_from_slice(&bytes).expect("Invalid state version"),
+            None => 1,
+        };
+        assert!(version != STATE_VERSION, "State is already at version {}", STATE_VERSION);
+
+        // version 1 is the only layout released before this one
+        let old: BlogV1 = env::state_read().expect("Contract is not initialized");
+
+        let caller = env::predecessor_account_id();
+        assert!(caller == old.owner || caller == env::current_account_id(), "Only owner can migrate the contract");
+
+        Self::write_state_version();
+
+        Self {
+            owner: old.owner,
+            proposed_owner: None,
+            // the version 1 collections still use the old prefixes
+            user_posts: UnorderedMap::new(b"user_posts_v2".to_vec()),
+            posts: UnorderedMap::new(b"posts_v2".to_vec()),
+            post_index: TreeMap::new(b"post_index".to_vec()),
+            top_index: TreeMap::new(b"top_index".to_vec()),
+            hot_index: TreeMap::new(b"hot_index".to_vec()),
+            controversial_index: TreeMap::new(b"controversial_index".to_vec()),
+            time_index: TreeMap::new(b"time_index".to_vec()),
+            tag_posts: TreeMap::new(b"tag_posts".to_vec()),
+            tag_counts: LookupMap::new(b"tag_counts".to_vec()),
+            tag_ranking: TreeMap::new(b"tag_ranking".to_vec()),
+            comments: UnorderedMap::new(b"comments_v2".to_vec()),
+            revisions: LookupMap::new(b"revisions".to_vec()),
+            revision_counts: LookupMap::new(b"revision_counts".to_vec()),
+            roles: UnorderedMap::new(b"roles".to_vec()),
+            post_votes: LookupMap::new(b"post_votes".to_vec()),
+            post_reactions: LookupMap::new(b"post_reactions".to_vec()),
+            post_reaction_kinds: default_post_reaction_kinds(),
+            comment_votes: LookupMap::new(b"comment_votes".to_vec()),
+            report_cases: UnorderedMap::new(b"report_cases".to_vec()),
+            report_queue: TreeMap::new(b"report_queue".to_vec()),
+            report_threshold: DEFAULT_REPORT_THRESHOLD,
+            sanctions: UnorderedMap::new(b"sanctions".to_vec()),
+            rate_limits: RateLimits::default(),
+            action_logs: LookupMap::new(b"action_logs".to_vec()),
+            comment_reactions: LookupMap::new(b"comment_reactions".to_vec()),
+            comment_threads: TreeMap::new(b"comment_threads".to_vec()),
+            storage_accounts: LookupMap::new(b"storage_accounts".to_vec()),
+            orphaned_bytes: LookupMap::new(b"orphaned_bytes".to_vec()),
+            profiles: LookupMap::new(b"profiles".to_vec()),
+            following: TreeMap::new(b"following".to_vec()),
+            followers: TreeMap::new(b"followers".to_vec()),
+            follow_counts: LookupMap::new(b"follow_counts".to_vec()),
+
+            next_post_id: old.next_post_id,
+            next_comment_id: old.next_comment_id,
+            next_donation_id: old.next_donation_id,
+            config: Config::default(),
+            paused_features: Vec::new(),
+            legacy: Some(LegacyV1 {
+                user_posts: old.user_posts,
+                posts: old.posts,
+                comments: old.comments,
+                post_progress: None,
+            }),
+        }
+    }
+
+    /// Moves up to `limit` version 1 posts, comments and votes, then drops what version 1 left behind.
+    /// A post with more comments and votes than fit in a batch is moved over several calls.
+    /// Call it until it returns `true`, the posts show up once all of their comments and votes are moved.
+    pub fn migrate_batch(&mut self, limit: usize) -> bool {
+        let caller = env::predecessor_account_id();
+        assert!(caller == self.owner || caller == env::current_account_id(), "Only owner can migrate the contract");
+        assert!(limit > 0 && limit <= MAX_MIGRATION_BATCH, "Limit must be between 1 and {}", MAX_MIGRATION_BATCH);
+
+        let mut legacy = match self.legacy.take() {
+            Some(legacy) => legacy,
+            None => return true,
+        };
+
+        let mut budget = limit;
+        while budget > 0 {
+            if let Some((post_id, post)) = legacy.pop_post() {
+                budget = self.migrate_post_v1(&mut legacy, post_id, post, budget);
+            } else if legacy.pop_comment().is_some() {
+                // comments of posts deleted in version 1 were never linked again, they are dropped
+                budget -= 1;
+            } else if legacy.pop_user_posts().is_some() {
+                // `user_posts` was rebuilt from the moved posts, without the posts deleted in version 1
+                budget -= 1;
+            } else {
+                break;
+            }
+        }
+
+        if !legacy.is_empty() {
+            self.legacy = Some(legacy);
+            return false;
+        }
+
+        BlogEvent::StateMigrated { state_version: STATE_VERSION }.emit();
+
+        true
+    }
+
+    pub fn get_state_version(&self) -> u32 {
+        // this code only runs on its own layout, a state without the key is a new blog
+        match env::storage_read(STATE_VERSION_KEY) {
+            Some(bytes) => u32::try_from_slice(&bytes).expect("Invalid state version"),
+            None => STATE_VERSION,
+        }
+    }
+
+    /// Whether `migrate_batch` still has version 1 content to move.
+    pub fn is_migrating(&self) -> bool {
+        self.legacy.is_some()
+    }
+
+    /// Creates a post, published right away unless `status` makes it a draft or schedules it.
+    /// With `off_chain` the body is stored elsewhere and `body` must be empty.
+    pub fn create_post(&mut self, title: String, body: String, tags: Option<Vec<String>>, status: Option<PostStatus>, off_chain: Option<OffChainContent>) -> usize {
+        self.assert_not_paused(Feature::Posting);
+        self.assert_not_banned(&env::predecessor_account_id());
+        let initial_storage_usage = env::storage_usage();
+        self.record_action(&env::predecessor_account_id(), RateLimitedAction::Post);
         let post_id = self.next_post_id;
+        let tags = normalize_tags(tags.unwrap_or_default(), &self.config);
+        let content = self.get_post_content(&title, &body, off_chain);
 
-        let post =  Post::new(post_id, title, body, env::predecessor_account_id(), env::block_timestamp());
+        let status = status.unwrap_or(PostStatus::Published);
+        if let PostStatus::Scheduled { publish_at } = status {
+            assert!(publish_at > env::block_timestamp(), "Scheduled time must be in the future");
+        }
+
+        let mut post =  Post::new(post_id, title, body, content, tags, env::predecessor_account_id(), env::block_timestamp(), status);
+
+        // posts of accounts on probation wait in the moderation queue
+        let held = self.has_sanction(&post.get_author(), SanctionKind::Probation);
+        if held {
+            post.set_hidden(true);
+        }
         
         self.posts.insert(&post_id, &post);
-        self.next_post_id = self.next_post_id + 1;
+        if post.is_indexed() {
+            self.index_post(&post);
+        }
+        if held {
+            self.hold_for_review(ReportTarget::Post { post_id });
+        }
+        self.next_post_id += 1;
 
         //push to user's post list
         let mut user_posts = self.user_posts.get(&env::predecessor_account_id()).unwrap_or(vec![]);
@@ -77,18 +397,188 @@ impl Blog {
         user_posts.push(post_id);
         self.user_posts.insert(&env::predecessor_account_id(), &user_posts); 
 
-        let title = post.get_title();
+        self.settle_storage(&post.get_author(), initial_storage_usage);
 
-        // Use env::log to record logs permanently to the blockchain!
-        env::log(format!("Post '{}' was created", title).as_bytes());
+        BlogEvent::PostCreated {
+            post_id,
+            author: post.get_author(),
+            title: post.get_title(),
+        }
+        .emit();
 
         post_id
     }
 
+    /// Replaces the title and body of a post, and its tags when `tags` is given. `off_chain` works like in `create_post`.
+    pub fn edit_post(&mut self, post_id: usize, title: String, body: String, tags: Option<Vec<String>>, off_chain: Option<OffChainContent>) {
+        self.assert_not_paused(Feature::Posting);
+        self.assert_not_banned(&env::predecessor_account_id());
+        let initial_storage_usage = env::storage_usage();
+        let mut post = match self.posts.get(&post_id) {
+            Some(post) => post,
+            None => panic!("Post does not exist"),
+        };
+
+        let editor = env::predecessor_account_id();
+        assert_eq!(post.get_author(), editor, "Only the author can edit this post");
+        assert!(!post.is_deleted(), "Post is deleted");
+        let content = self.get_post_content(&title, &body, off_chain);
+
+        // keep the current version, as its author wrote it, before overwriting it
+        let revision_id = self.revision_counts.get(&post_id).unwrap_or(0);
+        let revision = Revision::new(revision_id, post.get_title(), post.get_body(), post.get_content(), post.get_tags(), post.get_author(), post.get_updated_at());
+        self.revisions.insert(&(post_id, revision_id), &revision);
+        self.revision_counts.insert(&post_id, &(revision_id + 1));
+        // only the latest revisions are kept, the bytes of the oldest one pay for the new one
+        if revision_id >= MAX_REVISIONS_PER_POST {
+            self.revisions.remove(&(post_id, revision_id - MAX_REVISIONS_PER_POST));
+        }
+
+        let tags = match tags {
+            Some(tags) => normalize_tags(tags, &self.config),
+            None => post.get_tags(),
+        };
+        if let Some(visible_at) = self.post_index.get(&post_id) {
+            self.unindex_tags(post_id, &post.get_tags());
+            self.index_tags(post_id, &tags, visible_at);
+        }
+
+        post.edit(title, body, content, tags, env::block_timestamp());
+        self.posts.insert(&post_id, &post);
+
+        self.settle_storage(&editor, initial_storage_usage);
+
+        BlogEvent::PostEdited { post_id, editor, revision_id }.emit();
+    }
+
+    /// Lists up to `limit` kept revisions of a post, oldest first. `from_revision_id` is exclusive:
+    /// pass the last revision id of a page to get the next one.
+    pub fn get_post_revisions(&self, post_id: usize, from_revision_id: Option<usize>, limit: usize) -> Vec<Revision> {
+        assert!(limit > 0, "Limit must be greater than 0");
+        assert!(limit <= MAX_REVISIONS_PER_CALL, "At most {} revisions can be listed at once", MAX_REVISIONS_PER_CALL);
+
+        let count = self.revision_counts.get(&post_id).unwrap_or(0);
+        let oldest = count.saturating_sub(MAX_REVISIONS_PER_POST);
+        let start = from_revision_id.map_or(oldest, |from_revision_id| oldest.max(from_revision_id.saturating_add(1)));
+
+        (start..count.min(start.saturating_add(limit))).filter_map(|revision_id| self.revisions.get(&(post_id, revision_id))).collect()
+    }
+
+    pub fn get_post_revision(&self, post_id: usize, revision_id: usize) -> Option<Revision> {
+        self.revisions.get(&(post_id, revision_id))
+    }
+
     pub fn get_owner(&self) -> AccountId {
         self.owner.clone()
     }
 
+    /// First step of an ownership transfer, the proposed account has to accept it.
+    pub fn propose_owner(&mut self, new_owner: ValidAccountId) {
+        self.assert_not_banned(&env::predecessor_account_id());
+        assert_eq!(self.owner, env::predecessor_account_id(), "Only owner can propose a new owner");
+
+        let proposed_owner: AccountId = new_owner.into();
+        assert!(proposed_owner != self.owner, "Account is already the owner");
+        self.proposed_owner = Some(proposed_owner.clone());
+
+        BlogEvent::OwnerProposed {
+            owner: self.owner.clone(),
+            proposed_owner,
+        }
+        .emit();
+    }
+
+    pub fn accept_ownership(&mut self) {
+        self.assert_not_banned(&env::predecessor_account_id());
+        let new_owner = env::predecessor_account_id();
+        assert_eq!(self.proposed_owner, Some(new_owner.clone()), "Only the proposed owner can accept ownership");
+
+        // the owner implicitly holds every role
+        self.roles.remove(&new_owner);
+        self.proposed_owner = None;
+        let previous_owner = std::mem::replace(&mut self.owner, new_owner.clone());
+
+        BlogEvent::OwnershipTransferred { previous_owner, new_owner }.emit();
+    }
+
+    pub fn cancel_owner_proposal(&mut self) {
+        self.assert_not_banned(&env::predecessor_account_id());
+        assert_eq!(self.owner, env::predecessor_account_id(), "Only owner can cancel the proposal");
+
+        let proposed_owner = match self.proposed_owner.take() {
+            Some(proposed_owner) => proposed_owner,
+            None => panic!("No owner is proposed"),
+        };
+
+        BlogEvent::OwnerProposalCancelled {
+            owner: self.owner.clone(),
+            proposed_owner,
+        }
+        .emit();
+    }
+
+    pub fn get_proposed_owner(&self) -> Option<AccountId> {
+        self.proposed_owner.clone()
+    }
+
+    pub fn grant_role(&mut self, account_id: ValidAccountId, role: Role) {
+        self.assert_not_banned(&env::predecessor_account_id());
+        let account_id: AccountId = account_id.into();
+        let granted_by = env::predecessor_account_id();
+
+        assert!(role != Role::Owner, "The owner role cannot be granted");
+        assert!(account_id != self.owner, "The owner already holds every role");
+        assert!(
+            self.get_role(granted_by.clone()).is_some_and(|granter| granter.outranks(role)),
+            "Not allowed to grant this role"
+        );
+        if let Some(current) = self.roles.get(&account_id) {
+            assert!(
+                self.get_role(granted_by.clone()).unwrap().outranks(current),
+                "Not allowed to change the role of this account"
+            );
+        }
+
+        self.roles.insert(&account_id, &role);
+
+        BlogEvent::RoleGranted { account_id, role, granted_by }.emit();
+    }
+
+    pub fn revoke_role(&mut self, account_id: ValidAccountId) {
+        self.assert_not_banned(&env::predecessor_account_id());
+        let account_id: AccountId = account_id.into();
+        let revoked_by = env::predecessor_account_id();
+
+        let role = match self.roles.get(&account_id) {
+            Some(role) => role,
+            None => panic!("Account has no role"),
+        };
+        assert!(
+            self.get_role(revoked_by.clone()).is_some_and(|revoker| revoker.outranks(role)),
+            "Not allowed to revoke this role"
+        );
+
+        self.roles.remove(&account_id);
+
+        BlogEvent::RoleRevoked { account_id, role, revoked_by }.emit();
+    }
+
+    pub fn get_role(&self, account_id: AccountId) -> Option<Role> {
+        if account_id == self.owner {
+            return Some(Role::Owner);
+        }
+
+        self.roles.get(&account_id)
+    }
+
+    pub fn get_role_members(&self, role: Role) -> Vec<AccountId> {
+        if role == Role::Owner {
+            return vec![self.owner.clone()];
+        }
+
+        self.roles.iter().filter(|(_, member_role)| *member_role == role).map(|(account_id, _)| account_id).collect()
+    }
+
     pub fn get_post(&self, post_id: usize) -> Option<Post> {
         self.posts.get(&post_id)
     }
@@ -97,7 +587,11 @@ impl Blog {
         let mut posts = Vec::new();
 
         for post_id in self.posts.keys() {
-            posts.push(self.posts.get(&post_id).unwrap());
+            let post = self.posts.get(&post_id).unwrap();
+
+            if post.is_visible(env::block_timestamp()) {
+                posts.push(post);
+            }
         }
 
         posts
@@ -105,487 +599,3878 @@ impl Blog {
 
     pub fn get_user_posts(&self, user_id: AccountId) -> Vec<Post> {
         //if user_id has no post by checking length
-        if self.user_posts.get(&user_id).unwrap_or(vec![]).len() == 0 {
+        if self.user_posts.get(&user_id).unwrap_or(vec![]).is_empty() {
             return vec![];
         }
 
         let mut posts = Vec::new();
 
+        // deleted posts are no longer in the list, drafts and scheduled ones are skipped
         for post_id in self.user_posts.get(&user_id).unwrap() {
-            posts.push(self.posts.get(&post_id).unwrap());
+            if let Some(post) = self.posts.get(&post_id) {
+                if post.is_visible(env::block_timestamp()) {
+                    posts.push(post);
+                }
+            }
         }
 
         posts
     }
 
+    /// Lists the drafts of an author and the posts they scheduled that are not visible yet.
+    pub fn get_user_drafts(&self, user_id: AccountId) -> Vec<Post> {
+        self.user_posts
+            .get(&user_id)
+            .unwrap_or(vec![])
+            .iter()
+            .filter_map(|post_id| self.posts.get(post_id))
+            .filter(|post| !post.is_deleted() && !post.is_hidden() && !post.is_visible(env::block_timestamp()))
+            .collect()
+    }
+
+    /// Deprecated, use `get_posts_by_cursor` with `SortOrder::Ascending`, which costs the same on every page.
+    /// Pages skip the posts before them, so only the first `MAX_PAGED_POSTS` posts can be reached.
     pub fn get_paging_posts(&self, page: usize, page_size: usize) -> Vec<Post> {
         assert!(page_size > 0, "Page size must be greater than 0");
         assert!(page > 0, "Page must be greater than 0");
-
-        let mut posts = Vec::new();
-
+        assert!(
+            page.checked_mul(page_size).is_some_and(|end| end <= MAX_PAGED_POSTS),
+            "Only the first {} posts can be paged, use get_posts_by_cursor",
+            MAX_PAGED_POSTS
+        );
 
         //notice: page start from 1
         let start = (page - 1) * page_size;
-        let mut end = start + page_size;
-    
-        let total_posts = self.posts.len().try_into().unwrap_or(0);
 
-        if end > total_posts {
-            end = total_posts;
-        }
+        // walk the ordered index, the order of `posts` changes when a post is removed
+        self.post_index
+            .iter()
+            .filter(|(_, visible_at)| *visible_at <= env::block_timestamp())
+            .skip(start)
+            .take(page_size)
+            .map(|(post_id, _)| self.posts.get(&post_id).unwrap())
+            .collect()
+    }
 
-        let values = self.posts.values();
+    /// Lists up to `limit` posts by creation, newest first unless `order` is `Ascending`.
+    /// `from_post_id` is exclusive: pass the last post id of a page to get the next one.
+    pub fn get_posts_by_cursor(&self, from_post_id: Option<usize>, limit: usize, order: Option<SortOrder>) -> Vec<Post> {
+        assert!(limit > 0, "Limit must be greater than 0");
+        assert!(limit <= MAX_POSTS_PER_CALL, "At most {} posts can be listed at once", MAX_POSTS_PER_CALL);
 
-        for value in values.skip(start).take(end - start) {
-            posts.push(value);
-        }
+        let entries: Box<dyn Iterator<Item = (PostId, u64)>> = match (order.unwrap_or(SortOrder::Descending), from_post_id) {
+            (SortOrder::Ascending, Some(from_post_id)) => Box::new(self.post_index.iter_from(from_post_id)),
+            (SortOrder::Ascending, None) => Box::new(self.post_index.iter()),
+            (SortOrder::Descending, Some(from_post_id)) => Box::new(self.post_index.iter_rev_from(from_post_id)),
+            (SortOrder::Descending, None) => Box::new(self.post_index.iter_rev()),
+        };
 
-        posts
+        entries
+            .filter(|(_, visible_at)| *visible_at <= env::block_timestamp())
+            .take(limit)
+            .map(|(post_id, _)| self.posts.get(&post_id).unwrap())
+            .collect()
     }
 
-    pub fn get_total_posts(&self) -> u64 {
-        self.posts.len()
-    }
+    /// Lists up to `limit` posts tagged with `tag`, newest first.
+    /// `from_post_id` is exclusive: pass the last post id of a page to get the next one.
+    pub fn get_posts_by_tag(&self, tag: String, from_post_id: Option<PostId>, limit: usize) -> Vec<Post> {
+        assert!(limit > 0, "Limit must be greater than 0");
+        assert!(limit <= MAX_POSTS_PER_CALL, "At most {} posts can be listed at once", MAX_POSTS_PER_CALL);
 
-    pub fn delete_post(&mut self, post_id: usize) {
-        assert_eq!(self.owner, env::predecessor_account_id(), "Only owner can delete posts");
-        self.posts.remove(&post_id);
+        let tag = normalize_tags(vec![tag], &self.config).remove(0);
+
+        // the posts of a tag sit next to each other, starting below the highest possible id
+        self.tag_posts
+            .iter_rev_from((tag.clone(), from_post_id.unwrap_or(PostId::MAX)))
+            .take_while(|((post_tag, _), _)| *post_tag == tag)
+            .filter(|(_, visible_at)| *visible_at <= env::block_timestamp())
+            .take(limit)
+            .map(|((_, post_id), _)| self.posts.get(&post_id).unwrap())
+            .collect()
     }
 
-    pub fn create_comment(&mut self, post_id: usize, body: String) {
-        // Check if the post exists
-        let post = self.posts.get(&post_id);
-        assert!(post.is_some(), "Post does not exist");
-        assert!(body.len() >= 10, "Comment must be at least 10 characters long");
+    /// Returns the `limit` most used tags with their number of posts, scheduled posts count once they are scheduled.
+    pub fn get_top_tags(&self, limit: usize) -> Vec<(String, u64)> {
+        assert!(limit > 0, "Limit must be greater than 0");
+        assert!(limit <= MAX_TAGS_PER_CALL, "At most {} tags can be listed at once", MAX_TAGS_PER_CALL);
 
-        let author = env::predecessor_account_id();
-        let created_at = env::block_timestamp();
+        self.tag_ranking.iter().take(limit).map(|((_, tag), count)| (tag, count)).collect()
+    }
 
-        let comment = Comment::new(self.next_comment_id, body, author, created_at);
+    /// Lists the visible posts by `ranking`, optionally only those published in the last `window` nanoseconds.
+    /// A window ranks its latest `MAX_RANKED_WINDOW_POSTS` posts, older ones in it are left out.
+    pub fn get_ranked_posts(&self, ranking: Ranking, window: Option<u64>, page: usize, page_size: usize) -> Vec<Post> {
+        assert!(page_size > 0, "Page size must be greater than 0");
+        assert!(page > 0, "Page must be greater than 0");
 
-        match self.posts.get(&post_id).as_mut() {
-            Some(post) => {
-                post.add_comment(comment.get_comment_id());
-                self.posts.insert(&post_id, post);
+        let now = env::block_timestamp();
+        let window = match window {
+            Some(window) => window,
+            None => {
+                return self
+                    .ranking_index(ranking)
+                    .iter_rev()
+                    .filter(|(_, visible_at)| *visible_at <= now)
+                    .skip((page - 1) * page_size)
+                    .take(page_size)
+                    .map(|((_, post_id), _)| self.posts.get(&post_id).unwrap())
+                    .collect();
             },
-            None => panic!("Post does not exist"),
-        }
+        };
 
-        self.comments.insert(&comment.get_comment_id(), &comment);
-        self.next_comment_id = self.next_comment_id + 1;
+        // only the latest posts published in the window are read, then ranked like in the ranking index
+        let since = now.saturating_sub(window);
+        let mut ranked: Vec<(i64, PostId)> = self
+            .time_index
+            .iter_rev_from((now.saturating_add(1), 0))
+            .take_while(|((visible_at, _), _)| *visible_at >= since)
+            .take(MAX_RANKED_WINDOW_POSTS)
+            .map(|((visible_at, post_id), (upvotes, downvotes))| (ranking.rank(upvotes, downvotes, visible_at), post_id))
+            .collect();
+        ranked.sort_by(|a, b| b.cmp(a));
+
+        ranked
+            .into_iter()
+            .skip((page - 1) * page_size)
+            .take(page_size)
+            .map(|(_, post_id)| self.posts.get(&post_id).unwrap())
+            .collect()
     }
 
-    pub fn delete_comment(&mut self, post_id: usize, comment_id: usize) {
-        // only owner can delete comments
-        assert_eq!(self.owner, env::predecessor_account_id(), "Only owner can delete comments");
+    /// Counts the listed posts, drafts, scheduled and deleted ones are left out.
+    pub fn get_total_posts(&self) -> u64 {
+        // only the scheduled posts still waiting sit past the current time, they are the ones read
+        let scheduled = self.time_index.iter_rev().take_while(|((visible_at, _), _)| *visible_at > env::block_timestamp()).count();
 
-        // Check if the post exists
+        self.post_index.len() - scheduled as u64
+    }
+
+    /// Deletes a post, by its author or a moderator, along with its revisions.
+    /// The post keeps resolving by id with a tombstone in place of its content, but leaves every listing.
+    /// Its comments are left as they are and read as deleted with the post, so the cost doesn't grow with them.
+    pub fn delete_post(&mut self, post_id: usize, reason: Option<String>) {
+        self.assert_not_banned(&env::predecessor_account_id());
         let mut post = match self.posts.get(&post_id) {
             Some(post) => post,
             None => panic!("Post does not exist"),
         };
-        
-        let comment = self.comments.get(&comment_id).unwrap();
-        assert!(comment.get_comment_id() == comment_id, "Comment does not exist");
-        
-        post.remove_comment(comment_id);
-    }
+        assert!(!post.is_deleted(), "Post is already deleted");
 
-    #[payable]
-    pub fn donate(&mut self, post_id: usize, amount: u128, message: String) {
-        // Check if the post exists
-        let post = self.posts.get(&post_id).unwrap();
-        assert!(post.get_post_id() == post_id, "Post does not exist");
+        let deleted_by = env::predecessor_account_id();
+        let author = post.get_author();
+        assert!(
+            deleted_by == author || self.has_role(&deleted_by, Role::Moderator),
+            "Only the author or a moderator can delete this post"
+        );
 
-        // check if the amount is valid
-        assert!(amount >= 1, "Amount must be greater than 0");
-        // enough balance
-        assert!(env::account_balance() >= amount, "Not enough balance");
+        let initial_storage_usage = env::storage_usage();
 
+        self.unindex_post(&post);
+        // the earlier versions go with the post, their bytes are released to the author
+        if let Some(count) = self.revision_counts.remove(&post_id) {
+            for revision_id in count.saturating_sub(MAX_REVISIONS_PER_POST)..count {
+                self.revisions.remove(&(post_id, revision_id));
+            }
+        }
 
-        // transfer NEAR to the post author
-        let author = post.get_author();
-        let amount = amount;
-        
-        Promise::new(author).transfer(amount).then(self.save_to_donation_log(post_id, amount, message));
-    }
+        let mut user_posts = self.user_posts.get(&author).unwrap_or(vec![]);
+        user_posts.retain(|id| *id != post_id);
+        self.user_posts.insert(&author, &user_posts);
 
-    #[private]
-    fn save_to_donation_log(&mut self, post_id: usize, amount: u128, message: String) -> Promise {
-        let created_at = env::block_timestamp();
+        post.delete(Tombstone::new(deleted_by.clone(), env::block_timestamp(), reason.clone()));
+        self.posts.insert(&post_id, &post);
+        self.settle_moderation(&author, &deleted_by, initial_storage_usage);
 
-        let donation_log = DonationLog::new(self.next_donation_id, amount, env::predecessor_account_id(), created_at, message, post_id);
+        BlogEvent::PostDeleted {
+            post_id,
+            deleted_by,
+            reason,
+        }
+        .emit();
+    }
 
-        self.next_comment_id = self.next_comment_id + 1;
+    /// Turns a published or scheduled post back into a draft, hidden until its author publishes it again.
+    pub fn unpublish_post(&mut self, post_id: usize) {
+        self.assert_not_paused(Feature::Posting);
+        self.assert_not_banned(&env::predecessor_account_id());
+        let initial_storage_usage = env::storage_usage();
+        let mut post = match self.posts.get(&post_id) {
+            Some(post) => post,
+            None => panic!("Post does not exist"),
+        };
 
-        // save to donation log
-        let mut post = self.posts.get(&post_id).unwrap();
-        post.add_donation_logs(donation_log);
-        self.posts.insert(&post_id, &post);
+        let author = env::predecessor_account_id();
+        assert_eq!(post.get_author(), author, "Only the author can unpublish this post");
+        assert!(!post.is_deleted(), "Post is deleted");
+        assert!(post.get_status() != PostStatus::Draft, "Post is already a draft");
 
-        let donor = env::predecessor_account_id();
+        self.unindex_post(&post);
+        post.set_status(PostStatus::Draft);
+        self.posts.insert(&post_id, &post);
 
-        //Mark the promise as fulfilled by doing nothing
-        Promise::new(donor)
-    }
+        self.settle_storage(&author, initial_storage_usage);
 
-    pub fn get_next_post_id(&self) -> usize {
-        self.next_post_id
+        BlogEvent::PostUnpublished { post_id, author }.emit();
     }
 
-    pub fn get_comments(&self, post_id: usize) -> Vec<Comment> {
-        let post = self.posts.get(&post_id).unwrap();
-        
-        let mut comments = Vec::new();
-        for comment_id in post.get_comments() {
-            comments.push(self.comments.get(&comment_id).unwrap());
-        }
-        comments
-    }
+    /// Publishes a draft or a scheduled post now, or schedules it for `publish_at` (nanoseconds).
+    pub fn publish_post(&mut self, post_id: usize, publish_at: Option<u64>) {
+        self.assert_not_paused(Feature::Posting);
+        self.assert_not_banned(&env::predecessor_account_id());
+        let initial_storage_usage = env::storage_usage();
+        let mut post = match self.posts.get(&post_id) {
+            Some(post) => post,
+            None => panic!("Post does not exist"),
+        };
 
-    pub fn get_paging_comments(self, post_id: usize, page: usize, page_size: usize) -> Vec<Comment> {
-        assert!(page_size > 0, "Page size must be greater than 0");
-        assert!(page > 0, "Page must be greater than 0");
+        let author = env::predecessor_account_id();
+        assert_eq!(post.get_author(), author, "Only the author can publish this post");
+        assert!(!post.is_deleted(), "Post is deleted");
 
-        let post = self.posts.get(&post_id).unwrap();
-        let mut comments = Vec::new();
+        let status = match publish_at {
+            Some(publish_at) => {
+                assert!(publish_at > env::block_timestamp(), "Scheduled time must be in the future");
+                PostStatus::Scheduled { publish_at }
+            },
+            None => {
+                assert!(post.get_status() != PostStatus::Published, "Post is already published");
+                PostStatus::Published
+            },
+        };
 
-        let mut start = (page - 1) * page_size;
-        let mut end = start + page_size;
-         
-        if end > post.get_comments().len() {
-            end = post.get_comments().len();
-        }
+        let publish_at = match status {
+            PostStatus::Scheduled { publish_at } => publish_at,
+            _ => env::block_timestamp(),
+        };
 
-        for comment_id in post.get_comments() {
-            if start < end {
-                start = start + 1;
-                comments.push(self.comments.get(&comment_id).unwrap());
-            }
+        self.unindex_post(&post);
+        post.set_status(status);
+        self.posts.insert(&post_id, &post);
+        // a hidden post stays out of the indexes until a moderator clears it
+        if post.is_indexed() {
+            self.index_post(&post);
         }
 
-        comments
-    }
-
-    pub fn get_total_comments(&self) -> u64 {
-        self.comments.len()
-    }
+        self.settle_storage(&author, initial_storage_usage);
 
-    pub fn get_comment(&self, comment_id: usize) -> Comment {
-        self.comments.get(&comment_id).unwrap()
+        BlogEvent::PostPublished { post_id, author, publish_at }.emit();
     }
 
-    pub fn get_post_total_comments(&self, post_id: usize) -> u64 {
+    pub fn create_comment(&mut self, post_id: usize, body: String, parent_id: Option<usize>) -> usize {
+        self.assert_not_paused(Feature::Commenting);
+        self.assert_not_banned(&env::predecessor_account_id());
+        // Check if the post exists
         let post = match self.posts.get(&post_id) {
             Some(post) => post,
             None => panic!("Post does not exist"),
         };
-        
-        post.get_comments().len().try_into().unwrap()
-    }
+        assert!(post.is_visible(env::block_timestamp()), "Post is deleted or not published");
+        self.config.assert_valid_comment(&body);
 
-    pub fn upvote(&mut self, post_id: usize) {
-        let voter = env::predecessor_account_id(); 
-        
-        
-        match self.posts.get(&post_id).as_mut() {
-            Some(post) => {
-                post.add_upvote(voter);
-                self.posts.insert(&post_id, post);
-            },
-            None => panic!("Post does not exist"),
-        }
-    }
+        let initial_storage_usage = env::storage_usage();
+        let author = env::predecessor_account_id();
+        assert!(!self.has_sanction(&author, SanctionKind::Mute), "Account is muted");
+        self.record_action(&author, RateLimitedAction::Comment);
+        let created_at = env::block_timestamp();
+        let comment_id = self.next_comment_id;
 
-    pub fn remove_upvote(&mut self, post_id: usize) {
-        let mut post = match self.posts.get(&post_id) {
-            Some(post) => post,
-            None => panic!("Post does not exist"),
+        // replies are attached to their parent, which must belong to the same post
+        let depth = match parent_id {
+            Some(parent_id) => {
+                let parent = match self.comments.get(&parent_id) {
+                    Some(parent) => parent,
+                    None => panic!("Parent comment does not exist"),
+                };
+                assert_eq!(parent.get_post_id(), post_id, "Parent comment belongs to another post");
+                assert!(parent.is_listed(), "Parent comment is deleted or hidden");
+                assert!(parent.get_depth() < self.config.max_comment_depth, "Maximum reply depth reached");
+
+                parent.get_depth() + 1
+            },
+            None => 0,
         };
 
-        let voter = env::predecessor_account_id(); 
-        post.remove_upvote(voter);
-        
-        self.posts.insert(&post_id, &post);
-    }
+        let mut comment = Comment::new(comment_id, post_id, parent_id, depth, body, author.clone(), created_at);
 
-    pub fn downvote(&mut self, post_id: usize) {
-        let voter = env::predecessor_account_id(); 
+        if self.has_sanction(&author, SanctionKind::Probation) {
+            comment.set_hidden(true);
+            self.hold_for_review(ReportTarget::Comment { comment_id });
+        } else {
+            self.attach_comment(&comment);
+        }
 
         match self.posts.get(&post_id).as_mut() {
             Some(post) => {
-                post.add_downvote(voter);
+                post.add_comment(comment.get_comment_id());
                 self.posts.insert(&post_id, post);
             },
             None => panic!("Post does not exist"),
         }
-    }
 
-    pub fn remove_downvote(&mut self, post_id: usize) {
-        let mut post =  match self.posts.get(&post_id) {
-            Some(post) => post,
-            None => panic!("Post does not exist"),
-        };        
-        let voter = env::predecessor_account_id(); 
-        post.remove_downvote(voter);
-        
-        self.posts.insert(&post_id, &post);
-    }
+        self.comments.insert(&comment.get_comment_id(), &comment);
+        self.next_comment_id += 1;
 
-    pub fn get_votes_statistics(&self, post_id: usize) -> (usize, usize) {
-        let post =  match self.posts.get(&post_id) {
-            Some(post) => post,
-            None => panic!("Post does not exist"),
-        };         
-        let upvotes = post.get_upvotes().len();
-        let downvotes = post.get_downvotes().len();
+        self.settle_storage(&author, initial_storage_usage);
+
+        BlogEvent::CommentCreated {
+            comment_id,
+            post_id,
+            parent_id,
+            author,
+        }
+        .emit();
 
-        (upvotes, downvotes)
+        comment_id
     }
 
-    pub fn get_user_vote_status(&self, post_id: usize, user_id: AccountId) -> VoteStatus {
+    /// Deletes a comment, by its author or a moderator. Its replies are kept.
+    pub fn delete_comment(&mut self, post_id: usize, comment_id: usize, reason: Option<String>) {
+        self.assert_not_banned(&env::predecessor_account_id());
+        let initial_storage_usage = env::storage_usage();
+
+        // Check if the post exists
+        assert!(self.posts.get(&post_id).is_some(), "Post does not exist");
+
+        let mut comment = match self.comments.get(&comment_id) {
+            Some(comment) => comment,
+            None => panic!("Comment does not exist"),
+        };
+        assert_eq!(comment.get_post_id(), post_id, "Comment belongs to another post");
+        assert!(!comment.is_deleted(), "Comment is already deleted");
+
+        let deleted_by = env::predecessor_account_id();
+        let author = comment.get_author();
+        assert!(
+            deleted_by == author || self.has_role(&deleted_by, Role::Moderator),
+            "Only the author or a moderator can delete this comment"
+        );
+
+        comment.delete(Tombstone::new(deleted_by.clone(), env::block_timestamp(), reason.clone()));
+        self.comments.insert(&comment_id, &comment);
+        self.detach_comment(&comment);
+        self.settle_moderation(&author, &deleted_by, initial_storage_usage);
+
+        BlogEvent::CommentDeleted {
+            comment_id,
+            post_id,
+            deleted_by,
+            reason,
+        }
+        .emit();
+    }
+
+    #[payable]
+    pub fn donate(&mut self, post_id: usize, message: String) -> Promise {
+        self.assert_not_paused(Feature::Donations);
+        self.assert_not_banned(&env::predecessor_account_id());
+        // Check if the post exists
+        let post = match self.posts.get(&post_id) {
+            Some(post) => post,
+            None => panic!("Post does not exist"),
+        };
+        assert!(post.is_visible(env::block_timestamp()), "Post is deleted or not published");
+
+        // the donation is funded by the attached deposit
+        let amount = env::attached_deposit();
+        assert!(amount > 0, "Attached deposit must be greater than 0");
+
+        let donor = env::predecessor_account_id();
+
+        // transfer NEAR to the post author, then record the donation once the transfer succeeded
+        Promise::new(post.get_author()).transfer(amount).then(ext_self::on_donation_transferred(
+            post_id,
+            donor,
+            U128(amount),
+            message,
+            &env::current_account_id(),
+            0,
+            GAS_FOR_DONATION_CALLBACK,
+        ))
+    }
+
+    #[private]
+    pub fn on_donation_transferred(&mut self, post_id: usize, donor: AccountId, amount: U128, message: String) -> bool {
+        assert_eq!(env::promise_results_count(), 1, "Expected a single promise result");
+
+        match env::promise_result(0) {
+            PromiseResult::Successful(_) => {
+                self.save_to_donation_log(post_id, None, donor, amount.0, message);
+                true
+            },
+            _ => {
+                // the author could not receive the donation, give it back
+                Promise::new(donor.clone()).transfer(amount.0);
+
+                BlogEvent::DonationRefunded {
+                    post_id,
+                    donor,
+                    token_id: None,
+                    amount,
+                }
+                .emit();
+
+                false
+            },
+        }
+    }
+
+    /// NEP-141 receiver: donates the transferred tokens to the post named in `msg`,
+    /// e.g. `{"post_id": 0, "message": "Thanks!"}`.
+    pub fn ft_on_transfer(&mut self, sender_id: ValidAccountId, amount: U128, msg: String) -> PromiseOrValue<U128> {
+        self.assert_not_paused(Feature::Donations);
+        self.assert_not_banned(sender_id.as_ref());
+        let token_id = env::predecessor_account_id();
+        let donation: DonationMessage = serde_json::from_str(&msg).expect("Invalid donation message");
+
+        let post = match self.posts.get(&donation.post_id) {
+            Some(post) => post,
+            None => panic!("Post does not exist"),
+        };
+        assert!(post.is_visible(env::block_timestamp()), "Post is deleted or not published");
+        assert!(amount.0 > 0, "Amount must be greater than 0");
+
+        // forward the tokens to the post author, unused tokens are refunded by the token contract
+        ext_ft::ft_transfer(
+            post.get_author(),
+            amount,
+            Some(format!("Donation to post {}", donation.post_id)),
+            &token_id,
+            1,
+            GAS_FOR_FT_TRANSFER,
+        )
+        .then(ext_self::on_ft_donation_transferred(
+            donation.post_id,
+            sender_id.into(),
+            token_id,
+            amount,
+            donation.message,
+            &env::current_account_id(),
+            0,
+            GAS_FOR_DONATION_CALLBACK,
+        ))
+        .into()
+    }
+
+    #[private]
+    pub fn on_ft_donation_transferred(&mut self, post_id: usize, donor: AccountId, token_id: AccountId, amount: U128, message: String) -> U128 {
+        assert_eq!(env::promise_results_count(), 1, "Expected a single promise result");
+
+        match env::promise_result(0) {
+            PromiseResult::Successful(_) => {
+                self.save_to_donation_log(post_id, Some(token_id.clone()), donor, amount.0, message);
+                U128(0)
+            },
+            // nothing was used, the token contract refunds the donor
+            _ => {
+                BlogEvent::DonationRefunded {
+                    post_id,
+                    donor,
+                    token_id: Some(token_id),
+                    amount,
+                }
+                .emit();
+
+                amount
+            },
+        }
+    }
+
+    pub fn get_post_total_donation(&self, post_id: usize, token_id: Option<AccountId>) -> U128 {
+        let post = match self.posts.get(&post_id) {
+            Some(post) => post,
+            None => panic!("Post does not exist"),
+        };
+
+        U128(post.get_total_donation(&token_id))
+    }
+
+    /// NEP-145: pays for the storage used by the posts, comments and votes of `account_id`, the caller by default.
+    #[payable]
+    pub fn storage_deposit(&mut self, account_id: Option<ValidAccountId>, registration_only: Option<bool>) -> StorageBalance {
+        let amount = env::attached_deposit();
+        let account_id: AccountId = account_id.map(|account_id| account_id.into()).unwrap_or_else(env::predecessor_account_id);
+        let min_balance = self.storage_min_balance();
+
+        match self.storage_accounts.get(&account_id) {
+            Some(mut account) => {
+                if registration_only.unwrap_or(false) {
+                    // already registered, nothing to pay for
+                    if amount > 0 {
+                        Promise::new(env::predecessor_account_id()).transfer(amount);
+                    }
+                } else {
+                    account.deposit(amount);
+                    self.storage_accounts.insert(&account_id, &account);
+                }
+            },
+            None => {
+                assert!(amount >= min_balance, "The attached deposit is less than the minimum storage balance");
+
+                let deposit = if registration_only.unwrap_or(false) {
+                    if amount > min_balance {
+                        Promise::new(env::predecessor_account_id()).transfer(amount - min_balance);
+                    }
+                    min_balance
+                } else {
+                    amount
+                };
+                // the content left by an earlier registration stays paid for by the deposit it kept
+                let mut account = StorageAccount::new(deposit);
+                account.set_orphaned_bytes(self.orphaned_bytes.remove(&account_id).unwrap_or(0));
+                self.storage_accounts.insert(&account_id, &account);
+
+                // nothing can be written before the first registration, a new blog records its version here
+                if !env::storage_has_key(STATE_VERSION_KEY) {
+                    Self::write_state_version();
+                }
+            },
+        }
+
+        BlogEvent::StorageDeposited {
+            account_id: account_id.clone(),
+            amount: U128(amount),
+        }
+        .emit();
+
+        self.storage_balance_of(account_id.try_into().unwrap()).unwrap()
+    }
+
+    /// NEP-145: withdraws `amount` of the available balance, everything available by default.
+    #[payable]
+    pub fn storage_withdraw(&mut self, amount: Option<U128>) -> StorageBalance {
+        assert_eq!(env::attached_deposit(), 1, "Requires attached deposit of exactly 1 yoctoNEAR");
+
+        let account_id = env::predecessor_account_id();
+        let mut account = match self.storage_accounts.get(&account_id) {
+            Some(account) => account,
+            None => panic!("Account {} is not registered", account_id),
+        };
+
+        let available = account.get_available(self.storage_min_balance(), env::storage_byte_cost());
+        let amount = amount.map(|amount| amount.0).unwrap_or(available);
+        assert!(amount <= available, "The amount is greater than the available storage balance");
+
+        account.withdraw(amount);
+        self.storage_accounts.insert(&account_id, &account);
+
+        if amount > 0 {
+            Promise::new(account_id.clone()).transfer(amount);
+        }
+
+        BlogEvent::StorageWithdrawn {
+            account_id: account_id.clone(),
+            amount: U128(amount),
+        }
+        .emit();
+
+        self.storage_balance_of(account_id.try_into().unwrap()).unwrap()
+    }
+
+    /// NEP-145: closes the storage account and returns its balance.
+    /// With `force`, an account that still has content gives up the part of the deposit paying for it.
+    /// Deleting that content later gives nothing back, even if the account registers again.
+    #[payable]
+    pub fn storage_unregister(&mut self, force: Option<bool>) -> bool {
+        assert_eq!(env::attached_deposit(), 1, "Requires attached deposit of exactly 1 yoctoNEAR");
+
+        let account_id = env::predecessor_account_id();
+        let account = match self.storage_accounts.get(&account_id) {
+            Some(account) => account,
+            None => return false,
+        };
+
+        let force = force.unwrap_or(false);
+        assert!(
+            account.get_used_bytes() == 0 || force,
+            "Can't unregister an account that still has posts, comments or votes, pass force to keep them"
+        );
+
+        self.storage_accounts.remove(&account_id);
+
+        let orphaned_bytes = account.get_used_bytes() + account.get_orphaned_bytes();
+        if orphaned_bytes > 0 {
+            self.orphaned_bytes.insert(&account_id, &orphaned_bytes);
+        }
+
+        let refund = account.get_deposit() - Balance::from(account.get_used_bytes()) * env::storage_byte_cost();
+        if refund > 0 {
+            Promise::new(account_id.clone()).transfer(refund);
+        }
+
+        BlogEvent::StorageUnregistered {
+            account_id,
+            refund: U128(refund),
+        }
+        .emit();
+
+        true
+    }
+
+    pub fn storage_balance_of(&self, account_id: ValidAccountId) -> Option<StorageBalance> {
+        self.storage_accounts.get(account_id.as_ref()).map(|account| StorageBalance {
+            total: U128(account.get_deposit()),
+            available: U128(account.get_available(self.storage_min_balance(), env::storage_byte_cost())),
+        })
+    }
+
+    pub fn storage_balance_bounds(&self) -> StorageBalanceBounds {
+        StorageBalanceBounds {
+            min: U128(self.storage_min_balance()),
+            max: None,
+        }
+    }
+
+    pub fn get_next_post_id(&self) -> usize {
+        self.next_post_id
+    }
+
+    pub fn get_comments(&self, post_id: usize) -> Vec<Comment> {
+        let post = self.posts.get(&post_id).unwrap();
+        if post.is_deleted() {
+            return vec![];
+        }
+
+        let mut comments = Vec::new();
+        for comment_id in post.get_comments() {
+            let comment = self.comments.get(&comment_id).unwrap();
+
+            if comment.is_listed() {
+                comments.push(comment);
+            }
+        }
+        comments
+    }
+
+    pub fn get_paging_comments(self, post_id: usize, page: usize, page_size: usize) -> Vec<Comment> {
+        assert!(page_size > 0, "Page size must be greater than 0");
+        assert!(page > 0, "Page must be greater than 0");
+
+        self.get_comments(post_id).into_iter().skip((page - 1) * page_size).take(page_size).collect()
+    }
+
+    /// Returns the comments of a post as threads: every top-level comment followed by its replies, depth first.
+    /// A page continues after `from_comment_id`, the last comment of the previous one.
+    pub fn get_post_comment_threads(&self, post_id: usize, from_comment_id: Option<CommentId>, limit: usize) -> Vec<Comment> {
+        let post = match self.posts.get(&post_id) {
+            Some(post) => post,
+            None => panic!("Post does not exist"),
+        };
+        if post.is_deleted() {
+            return vec![];
+        }
+
+        let first = match from_comment_id {
+            Some(_) => None,
+            None => self
+                .comment_threads
+                .ceil_key(&(post_id, 0))
+                .filter(|(thread_post_id, _)| *thread_post_id == post_id)
+                .and_then(|(_, comment_id)| self.comments.get(&comment_id)),
+        };
+
+        self.list_thread(post_id, None, first, from_comment_id, limit)
+    }
+
+    /// Returns a comment followed by all of its replies, depth first.
+    /// A page continues after `from_comment_id`, the last comment of the previous one.
+    pub fn get_comment_thread(&self, comment_id: usize, from_comment_id: Option<CommentId>, limit: usize) -> Vec<Comment> {
+        let comment = match self.read_comment(comment_id) {
+            Some(comment) => comment,
+            None => panic!("Comment does not exist"),
+        };
+        // the replies of a comment belong to the same post
+        if comment.is_deleted() && self.posts.get(&comment.get_post_id()).unwrap().is_deleted() {
+            return vec![];
+        }
+
+        // a comment left out of its thread has nothing to show
+        let first = match from_comment_id {
+            None if comment.is_listed() || !comment.get_children().is_empty() => self.comments.get(&comment_id),
+            _ => None,
+        };
+
+        self.list_thread(comment.get_post_id(), Some(comment_id), first, from_comment_id, limit)
+    }
+
+    pub fn get_total_comments(&self) -> u64 {
+        self.comments.len()
+    }
+
+    pub fn get_comment(&self, comment_id: usize) -> Comment {
+        self.read_comment(comment_id).unwrap()
+    }
+
+    pub fn get_post_total_comments(&self, post_id: usize) -> u64 {
+        let post = match self.posts.get(&post_id) {
+            Some(post) => post,
+            None => panic!("Post does not exist"),
+        };
+        if post.is_deleted() {
+            return 0;
+        }
+
+        post.get_comments()
+            .iter()
+            .filter(|comment_id| self.comments.get(comment_id).unwrap().is_listed())
+            .count()
+            .try_into()
+            .unwrap()
+    }
+
+    pub fn upvote(&mut self, post_id: usize) {
+        self.set_vote(post_id, VoteStatus::Upvoted);
+    }
+
+    pub fn remove_upvote(&mut self, post_id: usize) {
+        let voter = env::predecessor_account_id();
+
+        if self.get_user_vote_status(post_id, voter) == VoteStatus::Upvoted {
+            self.set_vote(post_id, VoteStatus::None);
+        }
+    }
+
+    pub fn downvote(&mut self, post_id: usize) {
+        self.set_vote(post_id, VoteStatus::Downvoted);
+    }
+
+    pub fn remove_downvote(&mut self, post_id: usize) {
+        let voter = env::predecessor_account_id();
+
+        if self.get_user_vote_status(post_id, voter) == VoteStatus::Downvoted {
+            self.set_vote(post_id, VoteStatus::None);
+        }
+    }
+
+    pub fn get_votes_statistics(&self, post_id: usize) -> (u64, u64) {
         let post =  match self.posts.get(&post_id) {
             Some(post) => post,
             None => panic!("Post does not exist"),
-        };        
-        let voter = user_id;
-        let upvotes = post.get_upvotes().contains(&voter);
-        let downvotes = post.get_downvotes().contains(&voter);
-
-        if upvotes && !downvotes {
-            VoteStatus::Upvoted
-        } else if !upvotes && downvotes {
-            VoteStatus::Downvoted
-        } else {
-            VoteStatus::None
+        };         
+
+        (post.get_upvote_count(), post.get_downvote_count())
+    }
+
+    pub fn get_user_vote_status(&self, post_id: usize, user_id: AccountId) -> VoteStatus {
+        assert!(self.posts.get(&post_id).is_some(), "Post does not exist");
+
+        self.post_votes.get(&(post_id, user_id)).unwrap_or(VoteStatus::None)
+    }
+
+    /// Reacts to a post with one of the configured kinds, replacing the previous reaction of the caller.
+    pub fn react_to_post(&mut self, post_id: usize, reaction: String) {
+        assert!(self.post_reaction_kinds.contains(&reaction), "Unknown reaction {}", reaction);
+
+        self.set_post_reaction(post_id, Some(reaction));
+    }
+
+    pub fn remove_post_reaction(&mut self, post_id: usize) {
+        self.set_post_reaction(post_id, None);
+    }
+
+    pub fn get_user_post_reaction(&self, post_id: usize, user_id: AccountId) -> Option<String> {
+        assert!(self.posts.get(&post_id).is_some(), "Post does not exist");
+
+        self.post_reactions.get(&(post_id, user_id))
+    }
+
+    /// Reaction tallies of a page of posts, unknown post ids are left out.
+    pub fn get_posts_reactions(&self, post_ids: Vec<usize>) -> HashMap<PostId, HashMap<String, u64>> {
+        assert!(post_ids.len() <= MAX_POSTS_PER_CALL, "At most {} posts can be resolved at once", MAX_POSTS_PER_CALL);
+
+        post_ids
+            .into_iter()
+            .filter_map(|post_id| self.posts.get(&post_id).map(|post| (post_id, post.get_reaction_counts())))
+            .collect()
+    }
+
+    /// The reactions `user_id` left on a page of posts.
+    pub fn get_user_posts_reactions(&self, post_ids: Vec<usize>, user_id: AccountId) -> HashMap<PostId, String> {
+        assert!(post_ids.len() <= MAX_POSTS_PER_CALL, "At most {} posts can be resolved at once", MAX_POSTS_PER_CALL);
+
+        post_ids
+            .into_iter()
+            .filter_map(|post_id| self.post_reactions.get(&(post_id, user_id.clone())).map(|reaction| (post_id, reaction)))
+            .collect()
+    }
+
+    /// Replaces the reactions offered on posts. Reactions already left with a removed kind are kept and can be removed.
+    pub fn set_post_reaction_kinds(&mut self, kinds: Vec<String>) {
+        self.assert_not_banned(&env::predecessor_account_id());
+        assert_eq!(self.owner, env::predecessor_account_id(), "Only owner can configure reactions");
+        assert_valid_post_reaction_kinds(&kinds);
+
+        self.post_reaction_kinds = kinds.clone();
+
+        BlogEvent::PostReactionKindsUpdated { kinds }.emit();
+    }
+
+    pub fn get_post_reaction_kinds(&self) -> Vec<String> {
+        self.post_reaction_kinds.clone()
+    }
+
+    pub fn upvote_comment(&mut self, comment_id: usize) {
+        self.set_comment_vote(comment_id, VoteStatus::Upvoted);
+    }
+
+    pub fn downvote_comment(&mut self, comment_id: usize) {
+        self.set_comment_vote(comment_id, VoteStatus::Downvoted);
+    }
+
+    pub fn remove_comment_vote(&mut self, comment_id: usize) {
+        self.set_comment_vote(comment_id, VoteStatus::None);
+    }
+
+    pub fn get_user_comment_vote_status(&self, comment_id: usize, user_id: AccountId) -> VoteStatus {
+        assert!(self.comments.get(&comment_id).is_some(), "Comment does not exist");
+
+        self.comment_votes.get(&(comment_id, user_id)).unwrap_or(VoteStatus::None)
+    }
+
+    /// Reacts to a comment, replacing the previous reaction of the caller.
+    pub fn react_to_comment(&mut self, comment_id: usize, reaction: Reaction) {
+        self.set_comment_reaction(comment_id, Some(reaction));
+    }
+
+    pub fn remove_comment_reaction(&mut self, comment_id: usize) {
+        self.set_comment_reaction(comment_id, None);
+    }
+
+    pub fn get_user_comment_reaction(&self, comment_id: usize, user_id: AccountId) -> Option<Reaction> {
+        assert!(self.comments.get(&comment_id).is_some(), "Comment does not exist");
+
+        self.comment_reactions.get(&(comment_id, user_id))
+    }
+
+    /// Reports a post or comment to the moderators. It is hidden from listings once
+    /// `report_threshold` accounts reported it, until a moderator reviews the case.
+    pub fn report(&mut self, target: ReportTarget, reason: String) {
+        self.assert_not_banned(&env::predecessor_account_id());
+        let initial_storage_usage = env::storage_usage();
+        let reporter = env::predecessor_account_id();
+        self.assert_reportable(&target);
+
+        let mut case = match self.report_cases.get(&target) {
+            Some(case) => case,
+            None => self.open_report_case(&target),
+        };
+        case.add_report(Report::new(reporter.clone(), reason, env::block_timestamp()));
+        self.report_cases.insert(&target, &case);
+
+        // remember the charge to give it back when the case is closed
+        case.set_last_report_storage(env::storage_usage() - initial_storage_usage);
+        self.report_cases.insert(&target, &case);
+        self.settle_storage(&reporter, initial_storage_usage);
+
+        let report_count = case.get_report_count();
+        BlogEvent::ContentReported {
+            target: target.clone(),
+            reporter: reporter.clone(),
+            report_count,
+        }
+        .emit();
+
+        if report_count >= self.report_threshold {
+            self.set_target_hidden(&target, true, &reporter);
+            case.set_hidden(true);
+            self.report_cases.insert(&target, &case);
+
+            BlogEvent::ContentHidden { target, report_count }.emit();
+        }
+    }
+
+    /// Lists up to `limit` open report cases, oldest first. `from` is exclusive: pass the opening time
+    /// and target of the last case of a page to get the next one.
+    pub fn get_report_queue(&self, from: Option<(u64, ReportTarget)>, limit: usize) -> Vec<ReportCase> {
+        assert!(limit > 0, "Limit must be greater than 0");
+
+        let targets: Box<dyn Iterator<Item = ((u64, ReportTarget), ())>> = match from {
+            Some(from) => Box::new(self.report_queue.iter_from(from)),
+            None => Box::new(self.report_queue.iter()),
+        };
+
+        targets.take(limit).map(|((_, target), _)| self.report_cases.get(&target).unwrap()).collect()
+    }
+
+    pub fn get_report_case(&self, target: ReportTarget) -> Option<ReportCase> {
+        self.report_cases.get(&target)
+    }
+
+    /// Upholds the reports against `target`: deletes it and closes its case.
+    pub fn resolve_report(&mut self, target: ReportTarget, reason: Option<String>) {
+        self.assert_not_banned(&env::predecessor_account_id());
+        self.assert_role(Role::Moderator, "Only moderators can review reports");
+        self.close_report_case(&target);
+
+        match target {
+            ReportTarget::Post { post_id } => {
+                if !self.posts.get(&post_id).unwrap().is_deleted() {
+                    self.delete_post(post_id, reason);
+                }
+            },
+            ReportTarget::Comment { comment_id } => {
+                let comment = self.read_comment(comment_id).unwrap();
+
+                if !comment.is_deleted() {
+                    self.delete_comment(comment.get_post_id(), comment_id, reason);
+                }
+            },
         }
+
+        BlogEvent::ReportResolved {
+            target,
+            moderator: env::predecessor_account_id(),
+        }
+        .emit();
+    }
+
+    /// Rejects the reports against `target`: closes its case and shows it again if it was hidden.
+    pub fn dismiss_report(&mut self, target: ReportTarget) {
+        self.assert_not_banned(&env::predecessor_account_id());
+        self.assert_role(Role::Moderator, "Only moderators can review reports");
+        let moderator = env::predecessor_account_id();
+
+        if self.close_report_case(&target).is_hidden() {
+            self.set_target_hidden(&target, false, &moderator);
+        }
+
+        BlogEvent::ReportDismissed { target, moderator }.emit();
+    }
+
+    /// Shows hidden content again while its case stays in the queue.
+    pub fn restore_content(&mut self, target: ReportTarget) {
+        self.assert_not_banned(&env::predecessor_account_id());
+        self.assert_role(Role::Moderator, "Only moderators can review reports");
+        let moderator = env::predecessor_account_id();
+
+        let hidden = match &target {
+            ReportTarget::Post { post_id } => self.posts.get(post_id).is_some_and(|post| post.is_hidden()),
+            ReportTarget::Comment { comment_id } => self.comments.get(comment_id).is_some_and(|comment| comment.is_hidden()),
+        };
+        assert!(hidden, "Content is not hidden");
+
+        self.set_target_hidden(&target, false, &moderator);
+        if let Some(mut case) = self.report_cases.get(&target) {
+            case.set_hidden(false);
+            self.report_cases.insert(&target, &case);
+        }
+
+        BlogEvent::ContentRestored { target, moderator }.emit();
+    }
+
+    pub fn set_report_threshold(&mut self, threshold: u64) {
+        self.assert_not_banned(&env::predecessor_account_id());
+        assert_eq!(self.owner, env::predecessor_account_id(), "Only owner can set the report threshold");
+        assert!(threshold > 0, "Threshold must be greater than 0");
+
+        self.report_threshold = threshold;
+
+        BlogEvent::ReportThresholdUpdated { threshold }.emit();
+    }
+
+    pub fn get_report_threshold(&self) -> u64 {
+        self.report_threshold
+    }
+
+    /// Replaces the content policy, checked when posts and comments are created or edited.
+    pub fn set_config(&mut self, config: Config) {
+        self.assert_not_banned(&env::predecessor_account_id());
+        self.assert_role(Role::Admin, "Only owner or admins can update the config");
+        config.assert_valid();
+
+        self.config = config.clone();
+
+        BlogEvent::ConfigUpdated {
+            config,
+            updated_by: env::predecessor_account_id(),
+        }
+        .emit();
+    }
+
+    pub fn get_config(&self) -> Config {
+        self.config.clone()
+    }
+
+    /// Stops all writes of a feature until it is unpaused, moderation keeps working.
+    pub fn pause(&mut self, feature: Feature) {
+        self.assert_not_banned(&env::predecessor_account_id());
+        self.assert_role(Role::Admin, "Only owner or admins can pause features");
+        assert!(!self.paused_features.contains(&feature), "{} is already paused", feature.get_name());
+
+        self.paused_features.push(feature);
+
+        BlogEvent::FeaturePaused {
+            feature,
+            paused_by: env::predecessor_account_id(),
+        }
+        .emit();
+    }
+
+    pub fn unpause(&mut self, feature: Feature) {
+        self.assert_not_banned(&env::predecessor_account_id());
+        self.assert_role(Role::Admin, "Only owner or admins can unpause features");
+        assert!(self.paused_features.contains(&feature), "{} is not paused", feature.get_name());
+
+        self.paused_features.retain(|paused| *paused != feature);
+
+        BlogEvent::FeatureUnpaused {
+            feature,
+            unpaused_by: env::predecessor_account_id(),
+        }
+        .emit();
+    }
+
+    pub fn get_paused_features(&self) -> Vec<Feature> {
+        self.paused_features.clone()
+    }
+
+    /// Sets how often accounts can take an action, `None` removes the limit. Accounts with a role are never limited.
+    pub fn set_rate_limit(&mut self, action: RateLimitedAction, limit: Option<RateLimit>) {
+        self.assert_not_banned(&env::predecessor_account_id());
+        assert_eq!(self.owner, env::predecessor_account_id(), "Only owner can set rate limits");
+
+        self.rate_limits.set(action, limit);
+
+        BlogEvent::RateLimitUpdated { action, limit }.emit();
+    }
+
+    pub fn get_rate_limit(&self, action: RateLimitedAction) -> Option<RateLimit> {
+        self.rate_limits.get(action)
+    }
+
+    /// How many more times an account can take an action right now, `None` when it is not limited.
+    pub fn get_rate_limit_quota(&self, account_id: AccountId, action: RateLimitedAction) -> Option<Quota> {
+        let limit = self.get_account_rate_limit(&account_id, action)?;
+        let mut log = self.action_logs.get(&(account_id, action)).unwrap_or_default();
+        limit.prune(&mut log, env::block_timestamp());
+
+        Some(limit.get_quota(&log))
+    }
+
+    /// Bans, mutes or puts an account on probation, until `expires_at` or permanently. Replaces its current sanction.
+    pub fn sanction_account(&mut self, account_id: ValidAccountId, kind: SanctionKind, reason: String, expires_at: Option<u64>) {
+        self.assert_not_banned(&env::predecessor_account_id());
+        self.assert_role(Role::Moderator, "Only moderators can sanction accounts");
+        let initial_storage_usage = env::storage_usage();
+        let account_id: AccountId = account_id.into();
+        let issued_by = env::predecessor_account_id();
+
+        if let Some(role) = self.get_role(account_id.clone()) {
+            assert!(
+                self.get_role(issued_by.clone()).is_some_and(|issuer_role| issuer_role.outranks(role)),
+                "Not allowed to sanction this account"
+            );
+        }
+
+        let sanction = Sanction::new(kind, reason.clone(), issued_by.clone(), env::block_timestamp(), expires_at);
+        self.sanctions.insert(&account_id, &sanction);
+
+        self.settle_storage(&issued_by, initial_storage_usage);
+
+        BlogEvent::AccountSanctioned {
+            account_id,
+            kind,
+            reason,
+            expires_at,
+            issued_by,
+        }
+        .emit();
+    }
+
+    pub fn lift_sanction(&mut self, account_id: ValidAccountId) {
+        self.assert_not_banned(&env::predecessor_account_id());
+        self.assert_role(Role::Moderator, "Only moderators can lift sanctions");
+        let initial_storage_usage = env::storage_usage();
+        let account_id: AccountId = account_id.into();
+        let lifted_by = env::predecessor_account_id();
+
+        let sanction = match self.sanctions.remove(&account_id) {
+            Some(sanction) => sanction,
+            None => panic!("Account is not sanctioned"),
+        };
+        self.settle_moderation(&sanction.get_issued_by(), &lifted_by, initial_storage_usage);
+
+        BlogEvent::SanctionLifted { account_id, lifted_by }.emit();
+    }
+
+    /// The current sanction of an account, expired ones are ignored.
+    pub fn get_sanction(&self, account_id: AccountId) -> Option<Sanction> {
+        self.get_active_sanction(&account_id)
+    }
+
+    /// Lists the sanctions in force with their reasons.
+    pub fn get_sanctions(&self, page: usize, page_size: usize) -> Vec<(AccountId, Sanction)> {
+        assert!(page_size > 0, "Page size must be greater than 0");
+        assert!(page > 0, "Page must be greater than 0");
+
+        self.sanctions
+            .iter()
+            .filter(|(_, sanction)| sanction.is_active(env::block_timestamp()))
+            .skip((page - 1) * page_size)
+            .take(page_size)
+            .collect()
+    }
+
+    /// Creates or replaces the profile of the caller, its storage is charged like posts.
+    pub fn set_profile(&mut self, display_name: String, bio: Option<String>, avatar: Option<String>, links: Option<Vec<String>>) {
+        self.assert_not_banned(&env::predecessor_account_id());
+        let initial_storage_usage = env::storage_usage();
+        let account_id = env::predecessor_account_id();
+
+        let profile = Profile::new(display_name, bio.unwrap_or_default(), avatar, links.unwrap_or_default(), env::block_timestamp());
+        self.profiles.insert(&account_id, &profile);
+
+        self.settle_storage(&account_id, initial_storage_usage);
+
+        BlogEvent::ProfileUpdated { account_id }.emit();
+    }
+
+    pub fn remove_profile(&mut self) {
+        self.assert_not_banned(&env::predecessor_account_id());
+        let initial_storage_usage = env::storage_usage();
+        let account_id = env::predecessor_account_id();
+
+        if self.profiles.remove(&account_id).is_some() {
+            self.settle_storage(&account_id, initial_storage_usage);
+
+            BlogEvent::ProfileRemoved { account_id }.emit();
+        }
+    }
+
+    pub fn get_profile(&self, account_id: AccountId) -> Option<Profile> {
+        self.profiles.get(&account_id)
+    }
+
+    /// Follows `account_id`, the caller pays for the storage on both sides.
+    pub fn follow(&mut self, account_id: ValidAccountId) {
+        self.assert_not_banned(&env::predecessor_account_id());
+        let initial_storage_usage = env::storage_usage();
+        let account_id: AccountId = account_id.into();
+        let follower = env::predecessor_account_id();
+        assert!(account_id != follower, "You can't follow yourself");
+
+        let key = (follower.clone(), account_id.clone());
+        assert!(!self.following.contains_key(&key), "Already following this account");
+        assert!(self.get_follow_statistics(follower.clone()).1 < MAX_FOLLOWING as u64, "Can't follow more than {} accounts", MAX_FOLLOWING);
+
+        self.following.insert(&key, &env::block_timestamp());
+        self.followers.insert(&(account_id.clone(), follower.clone()), &env::block_timestamp());
+        self.update_follow_counts(&follower, |(followers, following)| (followers, following + 1));
+        self.update_follow_counts(&account_id, |(followers, following)| (followers + 1, following));
+
+        self.settle_storage(&follower, initial_storage_usage);
+
+        BlogEvent::Followed { follower, account_id }.emit();
+    }
+
+    pub fn unfollow(&mut self, account_id: ValidAccountId) {
+        self.assert_not_banned(&env::predecessor_account_id());
+        let initial_storage_usage = env::storage_usage();
+        let account_id: AccountId = account_id.into();
+        let follower = env::predecessor_account_id();
+
+        assert!(self.following.remove(&(follower.clone(), account_id.clone())).is_some(), "Not following this account");
+        self.followers.remove(&(account_id.clone(), follower.clone()));
+        self.update_follow_counts(&follower, |(followers, following)| (followers, following - 1));
+        self.update_follow_counts(&account_id, |(followers, following)| (followers - 1, following));
+
+        self.settle_storage(&follower, initial_storage_usage);
+
+        BlogEvent::Unfollowed { follower, account_id }.emit();
+    }
+
+    pub fn is_following(&self, follower: AccountId, account_id: AccountId) -> bool {
+        self.following.contains_key(&(follower, account_id))
+    }
+
+    /// Returns the number of followers and of followed accounts.
+    pub fn get_follow_statistics(&self, account_id: AccountId) -> (u64, u64) {
+        self.follow_counts.get(&account_id).unwrap_or((0, 0))
+    }
+
+    /// Lists up to `limit` followers of `account_id` by account id. `from_account_id` is exclusive:
+    /// pass the last account of a page to get the next one.
+    pub fn get_followers(&self, account_id: AccountId, from_account_id: Option<AccountId>, limit: usize) -> Vec<AccountId> {
+        Self::list_follows(&self.followers, account_id, from_account_id, limit)
+    }
+
+    /// Lists up to `limit` accounts followed by `account_id`, like `get_followers`.
+    pub fn get_following(&self, account_id: AccountId, from_account_id: Option<AccountId>, limit: usize) -> Vec<AccountId> {
+        Self::list_follows(&self.following, account_id, from_account_id, limit)
+    }
+
+    /// Home timeline of `account_id`: the latest visible posts of the accounts it follows, newest first within
+    /// every `MAX_FEED_AUTHORS` of them by account id. Pass the `next` cursor of a page to get the next one.
+    pub fn get_feed(&self, account_id: AccountId, cursor: Option<FeedCursor>, limit: usize) -> FeedPage {
+        assert!(limit > 0, "Limit must be greater than 0");
+        assert!(limit <= MAX_POSTS_PER_CALL, "At most {} posts can be listed at once", MAX_POSTS_PER_CALL);
+
+        let cursor = cursor.unwrap_or_default();
+        // one account past the batch tells whether there is another one
+        let mut authors = Self::list_follows(&self.following, account_id, cursor.after_author.clone(), MAX_FEED_AUTHORS + 1);
+        let has_more_authors = authors.len() > MAX_FEED_AUTHORS;
+        authors.truncate(MAX_FEED_AUTHORS);
+        let from_post_id = cursor.from_post_id;
+        let mut feed = Vec::new();
+
+        // `user_posts` is ordered by id, the newest `limit` posts of every author are enough to merge
+        for author in authors.iter() {
+            let post_ids = self
+                .user_posts
+                .get(author)
+                .unwrap_or(vec![])
+                .into_iter()
+                .rev()
+                .filter(|post_id| from_post_id.is_none_or(|from_post_id| *post_id < from_post_id))
+                .filter(|post_id| self.is_released(post_id))
+                .take(limit);
+
+            feed.extend(post_ids);
+        }
+
+        // only the posts making the page are loaded
+        feed.sort_by_key(|post_id| Reverse(*post_id));
+        feed.truncate(limit);
+        let feed: Vec<Post> = feed.into_iter().filter_map(|post_id| self.posts.get(&post_id)).collect();
+
+        // a full page may have more posts from the same authors, otherwise move on to the next ones
+        let next = if feed.len() == limit {
+            Some(FeedCursor {
+                after_author: cursor.after_author,
+                from_post_id: feed.last().map(|post| post.get_post_id()),
+            })
+        } else if has_more_authors {
+            Some(FeedCursor {
+                after_author: authors.pop(),
+                from_post_id: None,
+            })
+        } else {
+            None
+        };
+
+        FeedPage { posts: feed, next }
+    }
+
+    /// Resolves the profiles of a page of authors, donors or voters in one call.
+    /// Accounts without a profile are left out of the result.
+    pub fn get_profiles(&self, account_ids: Vec<AccountId>) -> HashMap<AccountId, Profile> {
+        assert!(account_ids.len() <= MAX_PROFILES_PER_CALL, "At most {} profiles can be resolved at once", MAX_PROFILES_PER_CALL);
+
+        account_ids
+            .into_iter()
+            .filter_map(|account_id| self.profiles.get(&account_id).map(|profile| (account_id, profile)))
+            .collect()
+    }
+
+}
+
+impl Blog {
+    /// Reads the accounts on the other side of the follows of `account_id`, which sit next to each other in `follows`.
+    fn list_follows(follows: &TreeMap<(AccountId, AccountId), u64>, account_id: AccountId, from_account_id: Option<AccountId>, limit: usize) -> Vec<AccountId> {
+        assert!(limit > 0, "Limit must be greater than 0");
+        assert!(limit <= MAX_ACCOUNTS_PER_CALL, "At most {} accounts can be listed at once", MAX_ACCOUNTS_PER_CALL);
+
+        // no account id is empty, so the first follow of the account comes right after it
+        follows
+            .iter_from((account_id.clone(), from_account_id.unwrap_or_default()))
+            .take_while(|((key, _), _)| *key == account_id)
+            .take(limit)
+            .map(|((_, other), _)| other)
+            .collect()
+    }
+
+    fn update_follow_counts(&mut self, account_id: &AccountId, update: impl Fn((u64, u64)) -> (u64, u64)) {
+        match update(self.get_follow_statistics(account_id.clone())) {
+            (0, 0) => self.follow_counts.remove(account_id),
+            counts => self.follow_counts.insert(account_id, &counts),
+        };
+    }
+
+    fn write_state_version() {
+        env::storage_write(STATE_VERSION_KEY, &STATE_VERSION.try_to_vec().unwrap());
+    }
+
+    /// Moves a version 1 post, its comments and its votes into the current collections.
+    /// Moves the comments and votes of a version 1 post that fit in `budget`, then the post itself, each counting as one.
+    /// A post that does not fit is put back for the next batch. Returns what is left of the budget.
+    fn migrate_post_v1(&mut self, legacy: &mut LegacyV1, post_id: PostId, post: PostV1, budget: usize) -> usize {
+        let moved = match legacy.post_progress.take() {
+            Some((progress_post_id, moved)) if progress_post_id == post_id => moved,
+            _ => 0,
+        };
+        let total = post.comments.len() + post.upvotes.len() + post.downvotes.len() + 1;
+        let end = total.min(moved + budget);
+
+        // comments were only linked from their post
+        for comment_id in post.comments.iter().take(end).skip(moved) {
+            if let Some(comment) = legacy.comments.remove(comment_id) {
+                self.comments.insert(comment_id, &Comment::from_v1(comment, post_id));
+                self.comment_threads.insert(&(post_id, *comment_id), &());
+            }
+        }
+
+        // voters move out of the post into their own collection, sorted to pick up where the last batch stopped
+        let mut upvoters: Vec<&AccountId> = post.upvotes.iter().collect();
+        upvoters.sort();
+        let mut downvoters: Vec<&AccountId> = post.downvotes.iter().collect();
+        downvoters.sort();
+        let votes = upvoters
+            .into_iter()
+            .map(|voter| (voter, VoteStatus::Upvoted))
+            .chain(downvoters.into_iter().map(|voter| (voter, VoteStatus::Downvoted)));
+        let comment_count = post.comments.len();
+        for (voter, status) in votes.take(end.saturating_sub(comment_count)).skip(moved.saturating_sub(comment_count)) {
+            self.post_votes.insert(&(post_id, voter.clone()), &status);
+        }
+
+        if end < total {
+            legacy.push_post(post_id, &post, end);
+            return 0;
+        }
+
+        let created_at = post.created_at;
+        let post = Post::from(post);
+
+        self.post_index.insert(&post_id, &created_at);
+        self.rank_post(&post, created_at);
+        self.posts.insert(&post_id, &post);
+
+        // posts are moved in no particular order, keep the ids of every author sorted
+        let mut user_posts = self.user_posts.get(&post.get_author()).unwrap_or(vec![]);
+        if let Err(index) = user_posts.binary_search(&post_id) {
+            user_posts.insert(index, post_id);
+        }
+        self.user_posts.insert(&post.get_author(), &user_posts);
+
+        budget - (end - moved)
+    }
+
+    /// Charges `account_id` for the bytes written since `initial_storage_usage`, or gives back what was freed.
+    fn settle_storage(&mut self, account_id: &AccountId, initial_storage_usage: StorageUsage) {
+        let storage_usage = env::storage_usage();
+
+        if storage_usage >= initial_storage_usage {
+            let mut account = match self.storage_accounts.get(account_id) {
+                Some(account) => account,
+                None => panic!("Account {} is not registered, call storage_deposit first", account_id),
+            };
+
+            account.use_bytes(storage_usage - initial_storage_usage);
+            assert!(
+                account.get_deposit() >= self.storage_min_balance() + Balance::from(account.get_used_bytes()) * env::storage_byte_cost(),
+                "Not enough storage balance, call storage_deposit to add more"
+            );
+            self.storage_accounts.insert(account_id, &account);
+        } else {
+            self.release_storage(account_id, initial_storage_usage - storage_usage);
+        }
+    }
+
+    /// Gives the bytes freed by deleting, hiding or restoring content back to `author`. Bytes written, like a
+    /// tombstone outgrowing the content it replaces, are charged to `actor` so an author short on balance can't block a moderator.
+    fn settle_moderation(&mut self, author: &AccountId, actor: &AccountId, initial_storage_usage: StorageUsage) {
+        if env::storage_usage() < initial_storage_usage {
+            self.settle_storage(author, initial_storage_usage);
+        } else {
+            self.settle_storage(actor, initial_storage_usage);
+        }
+    }
+
+    /// Gives back bytes charged to `account_id` in an earlier call.
+    fn release_storage(&mut self, account_id: &AccountId, bytes: StorageUsage) {
+        if let Some(mut account) = self.storage_accounts.get(account_id) {
+            account.free_bytes(bytes);
+            self.storage_accounts.insert(account_id, &account);
+        } else if let Some(orphaned_bytes) = self.orphaned_bytes.get(account_id) {
+            // nobody gets the bytes of an unregistered account back
+            match orphaned_bytes.saturating_sub(bytes) {
+                0 => self.orphaned_bytes.remove(account_id),
+                orphaned_bytes => self.orphaned_bytes.insert(account_id, &orphaned_bytes),
+            };
+        }
+    }
+
+    fn storage_min_balance(&self) -> Balance {
+        Balance::from(STORAGE_REGISTRATION_BYTES) * env::storage_byte_cost()
+    }
+
+    /// Adds a post to the post and tag indexes, returns the time it becomes visible.
+    fn index_post(&mut self, post: &Post) -> u64 {
+        let visible_at = match post.get_status() {
+            PostStatus::Scheduled { publish_at } => publish_at,
+            _ => env::block_timestamp(),
+        };
+
+        self.post_index.insert(&post.get_post_id(), &visible_at);
+        self.rank_post(post, visible_at);
+        self.index_tags(post.get_post_id(), &post.get_tags(), visible_at);
+
+        visible_at
+    }
+
+    fn unindex_post(&mut self, post: &Post) {
+        if let Some(visible_at) = self.post_index.remove(&post.get_post_id()) {
+            self.unrank_post(post, visible_at);
+        }
+        self.unindex_tags(post.get_post_id(), &post.get_tags());
+    }
+
+    fn rank_post(&mut self, post: &Post, visible_at: u64) {
+        for ranking in Ranking::ALL.iter() {
+            let rank = ranking.rank(post.get_upvote_count(), post.get_downvote_count(), visible_at);
+            self.ranking_index_mut(*ranking).insert(&(rank, post.get_post_id()), &visible_at);
+        }
+        self.time_index.insert(&(visible_at, post.get_post_id()), &(post.get_upvote_count(), post.get_downvote_count()));
+    }
+
+    fn unrank_post(&mut self, post: &Post, visible_at: u64) {
+        for ranking in Ranking::ALL.iter() {
+            let rank = ranking.rank(post.get_upvote_count(), post.get_downvote_count(), visible_at);
+            self.ranking_index_mut(*ranking).remove(&(rank, post.get_post_id()));
+        }
+        self.time_index.remove(&(visible_at, post.get_post_id()));
+    }
+
+    fn ranking_index(&self, ranking: Ranking) -> &TreeMap<(i64, PostId), u64> {
+        match ranking {
+            Ranking::Top => &self.top_index,
+            Ranking::Hot => &self.hot_index,
+            Ranking::Controversial => &self.controversial_index,
+        }
+    }
+
+    fn ranking_index_mut(&mut self, ranking: Ranking) -> &mut TreeMap<(i64, PostId), u64> {
+        match ranking {
+            Ranking::Top => &mut self.top_index,
+            Ranking::Hot => &mut self.hot_index,
+            Ranking::Controversial => &mut self.controversial_index,
+        }
+    }
+
+    /// Whether a post is in `post_index` and has reached its publication time, like `Post::is_visible`.
+    fn is_released(&self, post_id: &PostId) -> bool {
+        self.post_index.get(post_id).is_some_and(|visible_at| visible_at <= env::block_timestamp())
+    }
+
+    fn index_tags(&mut self, post_id: PostId, tags: &[String], visible_at: u64) {
+        for tag in tags {
+            if self.tag_posts.insert(&(tag.clone(), post_id), &visible_at).is_none() {
+                let count = self.tag_counts.get(tag).unwrap_or(0);
+                self.set_tag_count(tag, count + 1);
+            }
+        }
+    }
+
+    fn unindex_tags(&mut self, post_id: PostId, tags: &[String]) {
+        for tag in tags {
+            if self.tag_posts.remove(&(tag.clone(), post_id)).is_some() {
+                let count = self.tag_counts.get(tag).unwrap_or(0);
+                self.set_tag_count(tag, count - 1);
+            }
+        }
+    }
+
+    /// Moves `tag` to its new place in `tag_ranking`, a tag without posts leaves it.
+    fn set_tag_count(&mut self, tag: &String, count: u64) {
+        if let Some(previous) = self.tag_counts.get(tag) {
+            self.tag_ranking.remove(&(u64::MAX - previous, tag.clone()));
+        }
+
+        if count > 0 {
+            self.tag_counts.insert(tag, &count);
+            self.tag_ranking.insert(&(u64::MAX - count, tag.clone()), &count);
+        } else {
+            self.tag_counts.remove(tag);
+        }
+    }
+
+    fn has_role(&self, account_id: &AccountId, role: Role) -> bool {
+        self.get_role(account_id.clone()).is_some_and(|held| held.includes(role))
+    }
+
+    fn assert_role(&self, role: Role, message: &str) {
+        assert!(self.has_role(&env::predecessor_account_id(), role), "{}", message);
+    }
+
+    fn get_account_rate_limit(&self, account_id: &AccountId, action: RateLimitedAction) -> Option<RateLimit> {
+        match self.get_role(account_id.clone()) {
+            Some(_) => None,
+            None => self.rate_limits.get(action),
+        }
+    }
+
+    /// Logs an action of the account, panics when it already used up its quota.
+    fn record_action(&mut self, account_id: &AccountId, action: RateLimitedAction) {
+        let limit = match self.get_account_rate_limit(account_id, action) {
+            Some(limit) => limit,
+            None => return,
+        };
+        let now = env::block_timestamp();
+        let key = (account_id.clone(), action);
+        let mut log = self.action_logs.get(&key).unwrap_or_default();
+        limit.prune(&mut log, now);
+
+        let quota = limit.get_quota(&log);
+        assert!(
+            quota.remaining > 0,
+            "Rate limit reached: at most {} {} every {} seconds, try again at {}",
+            limit.max_actions,
+            action.get_name(),
+            limit.window / 1_000_000_000,
+            quota.resets_at.unwrap_or(now)
+        );
+
+        log.push(now);
+        self.action_logs.insert(&key, &log);
+    }
+
+    fn get_target_author(&self, target: &ReportTarget) -> AccountId {
+        match target {
+            ReportTarget::Post { post_id } => self.posts.get(post_id).unwrap().get_author(),
+            ReportTarget::Comment { comment_id } => self.comments.get(comment_id).unwrap().get_author(),
+        }
+    }
+
+    /// Opens a case without reports for content that stays hidden until a moderator reviews it.
+    fn hold_for_review(&mut self, target: ReportTarget) {
+        let mut case = self.open_report_case(&target);
+        case.set_hidden(true);
+        self.report_cases.insert(&target, &case);
+
+        BlogEvent::ContentHidden { target, report_count: 0 }.emit();
+    }
+
+    fn get_active_sanction(&self, account_id: &AccountId) -> Option<Sanction> {
+        self.sanctions.get(account_id).filter(|sanction| sanction.is_active(env::block_timestamp()))
+    }
+
+    fn has_sanction(&self, account_id: &AccountId, kind: SanctionKind) -> bool {
+        self.get_active_sanction(account_id).is_some_and(|sanction| sanction.get_kind() == kind)
+    }
+
+    /// Checks the title and body of a post and tells where its body is kept.
+    fn get_post_content(&self, title: &str, body: &str, off_chain: Option<OffChainContent>) -> PostContent {
+        self.config.assert_valid_title(title);
+
+        match off_chain {
+            Some(content) => {
+                assert!(body.is_empty(), "Off-chain posts can't have an on-chain body");
+                content.assert_valid();
+                PostContent::OffChain(content)
+            },
+            None => {
+                self.config.assert_valid_body(body);
+                PostContent::OnChain
+            },
+        }
+    }
+
+    fn assert_not_paused(&self, feature: Feature) {
+        assert!(!self.paused_features.contains(&feature), "{} is paused", feature.get_name());
+    }
+
+    fn assert_not_banned(&self, account_id: &AccountId) {
+        assert!(!self.has_sanction(account_id, SanctionKind::Ban), "Account is banned");
+    }
+
+    fn assert_reportable(&self, target: &ReportTarget) {
+        let (deleted, hidden) = match target {
+            ReportTarget::Post { post_id } => match self.posts.get(post_id) {
+                Some(post) => (post.is_deleted(), post.is_hidden()),
+                None => panic!("Post does not exist"),
+            },
+            ReportTarget::Comment { comment_id } => match self.read_comment(*comment_id) {
+                Some(comment) => (comment.is_deleted(), comment.is_hidden()),
+                None => panic!("Comment does not exist"),
+            },
+        };
+
+        assert!(!deleted, "Content is deleted");
+        assert!(!hidden, "Content is already hidden pending review");
+    }
+
+    fn set_target_hidden(&mut self, target: &ReportTarget, hidden: bool, actor: &AccountId) {
+        let initial_storage_usage = env::storage_usage();
+
+        match target {
+            ReportTarget::Post { post_id } => {
+                let mut post = self.posts.get(post_id).unwrap();
+
+                // hidden posts leave the indexes like drafts
+                if hidden {
+                    self.unindex_post(&post);
+                }
+                post.set_hidden(hidden);
+                if !hidden && post.is_indexed() {
+                    self.index_post(&post);
+                }
+
+                self.posts.insert(post_id, &post);
+                self.settle_moderation(&post.get_author(), actor, initial_storage_usage);
+            },
+            ReportTarget::Comment { comment_id } => {
+                let mut comment = self.comments.get(comment_id).unwrap();
+                comment.set_hidden(hidden);
+
+                self.comments.insert(comment_id, &comment);
+                if hidden {
+                    self.detach_comment(&comment);
+                } else {
+                    self.attach_comment(&comment);
+                }
+                self.settle_moderation(&comment.get_author(), actor, initial_storage_usage);
+            },
+        }
+    }
+
+    /// Starts a case for `target` at the end of the queue, the caller stores it.
+    fn open_report_case(&mut self, target: &ReportTarget) -> ReportCase {
+        let case = ReportCase::new(target.clone(), env::block_timestamp());
+        self.report_queue.insert(&(case.get_opened_at(), target.clone()), &());
+
+        case
+    }
+
+    /// Removes the case of `target` from the queue and gives the reporters their storage back.
+    fn close_report_case(&mut self, target: &ReportTarget) -> ReportCase {
+        let initial_storage_usage = env::storage_usage();
+        let case = match self.report_cases.remove(target) {
+            Some(case) => case,
+            None => panic!("No open reports for this content"),
+        };
+        self.report_queue.remove(&(case.get_opened_at(), target.clone()));
+        let mut freed = initial_storage_usage - env::storage_usage();
+
+        for report in case.get_reports() {
+            self.release_storage(&report.get_reporter(), report.get_storage_bytes());
+            freed = freed.saturating_sub(report.get_storage_bytes());
+        }
+
+        // what is left was paid by the author of content held for review
+        let author = self.get_target_author(target);
+        self.release_storage(&author, freed);
+
+        case
+    }
+
+    fn save_to_donation_log(&mut self, post_id: usize, token_id: Option<AccountId>, donor: AccountId, amount: u128, message: String) {
+        let created_at = env::block_timestamp();
+
+        let donation_id = self.next_donation_id;
+        let donation_log = DonationLog::new(donation_id, amount, token_id.clone(), donor.clone(), created_at, message, post_id);
+
+        self.next_donation_id += 1;
+
+        // save to donation log
+        let mut post = self.posts.get(&post_id).unwrap();
+        post.add_donation_logs(donation_log);
+        self.posts.insert(&post_id, &post);
+
+        BlogEvent::DonationReceived {
+            post_id,
+            donation_id,
+            donor,
+            token_id,
+            amount: U128(amount),
+        }
+        .emit();
+    }
+
+    fn set_vote(&mut self, post_id: PostId, status: VoteStatus) {
+        self.assert_not_paused(Feature::Voting);
+        self.assert_not_banned(&env::predecessor_account_id());
+        let initial_storage_usage = env::storage_usage();
+        let mut post = match self.posts.get(&post_id) {
+            Some(post) => post,
+            None => panic!("Post does not exist"),
+        };
+        assert!(post.is_visible(env::block_timestamp()), "Post is deleted or not published");
+
+        let voter = env::predecessor_account_id();
+        self.record_action(&voter, RateLimitedAction::Vote);
+        let key = (post_id, voter.clone());
+        let previous = self.post_votes.get(&key).unwrap_or(VoteStatus::None);
+
+        match status {
+            VoteStatus::None => self.post_votes.remove(&key),
+            _ => self.post_votes.insert(&key, &status),
+        };
+
+        // the ranks follow the vote counts
+        let visible_at = self.post_index.get(&post_id).unwrap();
+        self.unrank_post(&post, visible_at);
+        post.update_vote_counts(&previous, &status);
+        self.rank_post(&post, visible_at);
+        self.posts.insert(&post_id, &post);
+
+        self.settle_storage(&voter, initial_storage_usage);
+
+        BlogEvent::PostVoted { post_id, voter, status }.emit();
+    }
+
+    fn set_post_reaction(&mut self, post_id: PostId, reaction: Option<String>) {
+        self.assert_not_paused(Feature::Voting);
+        self.assert_not_banned(&env::predecessor_account_id());
+        let initial_storage_usage = env::storage_usage();
+        let mut post = match self.posts.get(&post_id) {
+            Some(post) => post,
+            None => panic!("Post does not exist"),
+        };
+        assert!(post.is_visible(env::block_timestamp()), "Post is deleted or not published");
+
+        let account_id = env::predecessor_account_id();
+        let key = (post_id, account_id.clone());
+        let previous = match &reaction {
+            Some(reaction) => self.post_reactions.insert(&key, reaction),
+            None => self.post_reactions.remove(&key),
+        };
+
+        post.update_reaction_counts(previous, reaction.clone());
+        self.posts.insert(&post_id, &post);
+
+        self.settle_storage(&account_id, initial_storage_usage);
+
+        BlogEvent::PostReacted { post_id, account_id, reaction }.emit();
+    }
+
+    /// Loads a comment as readers see it, the comments of a deleted post read as deleted with it.
+    fn read_comment(&self, comment_id: CommentId) -> Option<Comment> {
+        let mut comment = self.comments.get(&comment_id)?;
+
+        if !comment.is_deleted() {
+            if let Some(tombstone) = self.posts.get(&comment.get_post_id()).and_then(|post| post.get_tombstone()) {
+                comment.delete(tombstone);
+            }
+        }
+
+        Some(comment)
+    }
+
+    /// Loads a comment that can still be voted or reacted on.
+    fn get_open_comment(&self, comment_id: CommentId) -> Comment {
+        let comment = match self.comments.get(&comment_id) {
+            Some(comment) => comment,
+            None => panic!("Comment does not exist"),
+        };
+        assert!(comment.is_listed(), "Comment is deleted or hidden");
+        assert!(
+            self.posts.get(&comment.get_post_id()).unwrap().is_visible(env::block_timestamp()),
+            "Post is deleted or not published"
+        );
+
+        comment
+    }
+
+    fn set_comment_vote(&mut self, comment_id: CommentId, status: VoteStatus) {
+        self.assert_not_paused(Feature::Voting);
+        self.assert_not_banned(&env::predecessor_account_id());
+        let initial_storage_usage = env::storage_usage();
+        let mut comment = self.get_open_comment(comment_id);
+
+        let voter = env::predecessor_account_id();
+        self.record_action(&voter, RateLimitedAction::Vote);
+        let key = (comment_id, voter.clone());
+        let previous = self.comment_votes.get(&key).unwrap_or(VoteStatus::None);
+
+        match status {
+            VoteStatus::None => self.comment_votes.remove(&key),
+            _ => self.comment_votes.insert(&key, &status),
+        };
+
+        comment.update_vote_counts(&previous, &status);
+        self.comments.insert(&comment_id, &comment);
+
+        self.settle_storage(&voter, initial_storage_usage);
+
+        BlogEvent::CommentVoted { comment_id, voter, status }.emit();
+    }
+
+    fn set_comment_reaction(&mut self, comment_id: CommentId, reaction: Option<Reaction>) {
+        self.assert_not_paused(Feature::Voting);
+        self.assert_not_banned(&env::predecessor_account_id());
+        let initial_storage_usage = env::storage_usage();
+        let mut comment = self.get_open_comment(comment_id);
+
+        let account_id = env::predecessor_account_id();
+        let key = (comment_id, account_id.clone());
+        let previous = match reaction {
+            Some(reaction) => self.comment_reactions.insert(&key, &reaction),
+            None => self.comment_reactions.remove(&key),
+        };
+
+        comment.update_reaction_counts(previous, reaction);
+        self.comments.insert(&comment_id, &comment);
+
+        self.settle_storage(&account_id, initial_storage_usage);
+
+        BlogEvent::CommentReacted { comment_id, account_id, reaction }.emit();
+    }
+
+    /// Adds a comment to its thread, and the parents it brings back as placeholders.
+    /// A deleted or hidden comment stays in its thread as a placeholder while it has replies left.
+    fn attach_comment(&mut self, comment: &Comment) {
+        match comment.get_parent_id() {
+            Some(parent_id) => {
+                let mut parent = self.comments.get(&parent_id).unwrap();
+                let was_empty = parent.get_children().is_empty();
+
+                parent.add_child(comment.get_comment_id());
+                self.comments.insert(&parent_id, &parent);
+
+                if was_empty && !parent.is_listed() {
+                    self.attach_comment(&parent);
+                }
+            },
+            None => {
+                self.comment_threads.insert(&(comment.get_post_id(), comment.get_comment_id()), &());
+            },
+        }
+    }
+
+    /// Takes a deleted or hidden comment without replies out of its thread, and the placeholders left without replies.
+    fn detach_comment(&mut self, comment: &Comment) {
+        if comment.is_listed() || !comment.get_children().is_empty() {
+            return;
+        }
+
+        match comment.get_parent_id() {
+            Some(parent_id) => {
+                let mut parent = self.comments.get(&parent_id).unwrap();
+
+                parent.remove_child(comment.get_comment_id());
+                self.comments.insert(&parent_id, &parent);
+                self.detach_comment(&parent);
+            },
+            None => {
+                self.comment_threads.remove(&(comment.get_post_id(), comment.get_comment_id()));
+            },
+        }
+    }
+
+    /// Lists up to `limit` comments of a thread depth first, starting at `first` or after `from_comment_id`.
+    /// With a `root` the walk stays among its replies, otherwise it goes on through the threads of the post.
+    fn list_thread(
+        &self,
+        post_id: PostId,
+        root: Option<CommentId>,
+        first: Option<Comment>,
+        from_comment_id: Option<CommentId>,
+        limit: usize,
+    ) -> Vec<Comment> {
+        assert!(limit > 0, "Limit must be greater than 0");
+        assert!(limit <= MAX_COMMENTS_PER_CALL, "Limit must be at most {}", MAX_COMMENTS_PER_CALL);
+
+        let mut next = match from_comment_id {
+            Some(from_comment_id) => {
+                let from = match self.comments.get(&from_comment_id) {
+                    Some(from) => from,
+                    None => panic!("Comment does not exist"),
+                };
+                assert_eq!(from.get_post_id(), post_id, "Comment belongs to another post");
+
+                self.next_in_thread(&from, root)
+            },
+            None => first,
+        };
+
+        let mut thread = Vec::new();
+        while thread.len() < limit {
+            let mut comment = match next {
+                Some(comment) => comment,
+                None => break,
+            };
+            next = self.next_in_thread(&comment, root);
+
+            if comment.is_hidden() {
+                comment.redact();
+            }
+            thread.push(comment);
+        }
+
+        thread
+    }
+
+    /// The comment after `comment` in a depth first walk, which reads no more comments than the thread is deep.
+    fn next_in_thread(&self, comment: &Comment, root: Option<CommentId>) -> Option<Comment> {
+        if let Some(child_id) = comment.get_children().first() {
+            return self.comments.get(child_id);
+        }
+
+        let mut comment_id = comment.get_comment_id();
+        let mut parent_id = comment.get_parent_id();
+        while Some(comment_id) != root {
+            match parent_id {
+                Some(id) => {
+                    let parent = self.comments.get(&id).unwrap();
+
+                    // replies are sorted by id, so a comment taken out of its thread still finds its place
+                    if let Some(sibling_id) = parent.get_children().into_iter().find(|child_id| *child_id > comment_id) {
+                        return self.comments.get(&sibling_id);
+                    }
+
+                    comment_id = id;
+                    parent_id = parent.get_parent_id();
+                },
+                // past the top of the thread, only the walk through a whole post goes on
+                None if root.is_none() => {
+                    let post_id = comment.get_post_id();
+
+                    return self
+                        .comment_threads
+                        .higher(&(post_id, comment_id))
+                        .filter(|(thread_post_id, _)| *thread_post_id == post_id)
+                        .and_then(|(_, comment_id)| self.comments.get(&comment_id));
+                },
+                None => return None,
+            }
+        }
+
+        None
+    }
+}
+
+/*
+ * The rest of this file holds the inline tests for the code above
+ * Learn more about Rust tests: https://doc.rust-lang.org/book/ch11-01-writing-tests.html
+ *
+ * To run from contract directory:
+ * cargo test -- --nocapture
+ *
+ * From project root, to run in combination with frontend tests:
+ * yarn test
+ *
+ */
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::{MockedBlockchain};
+    use near_sdk::{testing_env, VMContext};
+    use near_sdk::test_utils::{get_logs, testing_env_with_promise_results};
+
+    const ONE_NEAR: u128 = 1_000_000_000_000_000_000_000_000;
+
+    // mock the context for testing, notice "signer_account_id" that was accessed above from env::
+    fn get_context(input: Vec<u8>, is_view: bool) -> VMContext {
+        VMContext {
+            current_account_id: "decentrablog.npmrunstart.testnet".to_string(),
+            signer_account_id: "alice_near".to_string(),
+            signer_account_pk: vec![0, 1, 2],
+            predecessor_account_id: "alice_near".to_string(),
+            input,
+            block_index: 0,
+            block_timestamp: 0,
+            account_balance: 1000000000000000000000000,
+            account_locked_balance: 0,
+            storage_usage: 0,
+            attached_deposit: 0,
+            prepaid_gas: 10u64.pow(18),
+            random_seed: vec![0, 1, 2],
+            is_view,
+            output_data_receivers: vec![],
+            epoch_height: 19,
+        }
+    }
+
+    // mock a call from another account, keeping the storage written by previous calls and the block time
+    fn get_caller_context(predecessor_account_id: &str, attached_deposit: u128) -> VMContext {
+        let mut context = get_context(vec![], false);
+        context.predecessor_account_id = predecessor_account_id.to_string();
+        context.attached_deposit = attached_deposit;
+        context.storage_usage = env::storage_usage();
+        context.block_timestamp = env::block_timestamp();
+        context
+    }
+
+    // register the test accounts so they can pay for the storage they use, then call as alice again
+    fn register_accounts(contract: &mut Blog) {
+        for account_id in ["alice_near", "bob_near", "carol_near"].iter() {
+            testing_env!(get_caller_context(account_id, ONE_NEAR));
+            contract.storage_deposit(None, None);
+        }
+
+        testing_env!(get_caller_context("alice_near", 0));
+    }
+
+    #[test]
+    fn create_post() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+
+        //log id
+        env::log(format!("Debug here {}", contract.get_post(0).unwrap().get_post_id()).as_bytes());
+        
+        assert_eq!(
+            "This is the title".to_string(),
+            contract.get_post(0).unwrap().get_title()
+        );
+        assert_eq!(
+            "Lets go Brandon!".to_string(),
+            contract.get_post(0).unwrap().get_body()
+        );
+        assert_eq!(1, contract.get_total_posts());
+        assert_eq!(0, contract.get_post(0).unwrap().get_post_id());
+
+        //test get_user_posts
+        let user_posts = contract.get_user_posts("alice_near".to_string());
+        assert_eq!(1, user_posts.len());
+        assert_eq!(0, user_posts[0].get_post_id());
+    }
+
+    #[test]
+    fn delete_a_post_then_add_two_posts() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+        contract.delete_post(0, None);
+        
+        assert_eq!(0, contract.get_total_posts(), "Total posts should be 0");
+
+        // add a post
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+        assert_eq!(2, contract.get_total_posts());
+
+        //next post id
+        assert_eq!(3, contract.get_next_post_id());
+    }
+
+    #[test]
+    fn return_owner_account_id() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let contract = Blog::default();
+        assert_eq!(
+            "alice_near".to_string(),
+            contract.get_owner()
+        );
+    }
+
+    #[test]
+    fn create_comment() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+
+        // Create the first post
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+        contract.create_comment(0, "This is the comment".to_string(), None);
+
+        assert_eq!(
+            "This is the comment".to_string(),
+            contract.get_comment(0).get_body()
+        );
+        assert_eq!(0, contract.get_comment(0).get_comment_id());
+
+        contract.create_comment(0, "This is comment 2, id 1".to_string(), None);
+        contract.create_comment(0, "This is comment 3, id 2".to_string(), None);
+
+        // Check if the comments is there
+        assert_eq!(
+            "This is comment 2, id 1".to_string(),
+            contract.get_comment(1).get_body()
+        );
+        assert_eq!(
+            "This is comment 3, id 2".to_string(),
+            contract.get_comment(2).get_body()
+        );
+
+        let comments = contract.get_post(0).unwrap().get_comments();
+
+        //assert size of comments
+        assert_eq!(3, comments.len(), "Comments size is not 3");
+        assert_eq!(3, contract.get_post_total_comments(0), "get_post_total_comments is not 3");
+
+        //Check comment string
+        assert_eq!(
+            "This is comment 2, id 1".to_string(),
+            contract.get_comment(1).get_body()
+        );
+        assert_eq!(
+            "This is comment 3, id 2".to_string(),
+            contract.get_comment(2).get_body()
+        );
+    }
+
+    #[test]
+    fn upvote_test() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+
+        // Create the first post
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+
+        // Upvote the post
+        contract.upvote(0);
+
+        // Check if the upvote is there
+        assert_eq!(
+            1,
+            contract.get_post(0).unwrap().get_upvote_count()
+        );
+
+        // upvote 10 times 
+        for _ in 0..10 {
+            contract.upvote(0);
+        }
+
+        // downvote 5 times
+        for _ in 0..5 {
+            contract.downvote(0);
+        }
+
+        // check statistic, 10 times of upvote is 1 in an account
+        // 5 times of downvote is the same
+        assert_eq!(
+            (0, 1),
+            contract.get_votes_statistics(0)
+        );
+        
+    }
+
+    #[test]
+    fn test_paging_post() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+
+        // Loop 100 post and create them
+        for i in 0..45 {
+            // every post is created in its own transaction
+            testing_env!(get_caller_context("alice_near", 0));
+            contract.create_post(format!("This is the title {}", i), format!("Lets go Brandon! {}", i), None, None, None);
+        }
+
+        assert_eq!(45, contract.get_total_posts(), "Total post is not 45");
+        
+        // Check paging post call
+        // let posts = contract.get_paging_posts(1, 10);
+        // assert_eq!(10, posts.len(), "Paging post size is not 10");
+        // assert_eq!(0, posts[0].get_post_id(), "Paging post id is not 10");
+        // assert_eq!(9, posts[9].get_post_id(), "Paging post id is not 20");
+
+        // Check paging post call
+        let posts = contract.get_paging_posts(5, 10);
+
+        assert_eq!(5, posts.len(), "Paging post size is not 5");
+        assert_eq!(40, posts[0].get_post_id(), "Paging post id is not 40");
+        assert_eq!(44, posts[4].get_post_id(), "Paging post id is not 44");
+    }
+
+    #[test]
+    fn test_donation() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+
+        // Create the first post
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+
+        // Donate, the donation is only recorded once the transfer succeeded
+        testing_env!(get_caller_context("bob_near", 1000000));
+        contract.donate(0, "Support Trump for the USA".to_string());
+        assert_eq!(0, contract.get_post(0).unwrap().get_total_donation(&None));
+
+        // Resolve the transfer
+        testing_env_with_promise_results(get_caller_context("decentrablog.npmrunstart.testnet", 0), PromiseResult::Successful(vec![]));
+        assert!(contract.on_donation_transferred(0, "bob_near".to_string(), U128(1000000), "Support Trump for the USA".to_string()));
+
+        // Check if the donation is there
+        assert_eq!(
+            1000000,
+            contract.get_post(0).unwrap().get_total_donation(&None)
+        );
+    }
+
+    #[test]
+    fn failed_donation_is_not_recorded() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+
+        testing_env_with_promise_results(get_caller_context("decentrablog.npmrunstart.testnet", 0), PromiseResult::Failed);
+        assert!(!contract.on_donation_transferred(0, "bob_near".to_string(), U128(1000000), "Support Trump for the USA".to_string()));
+
+        assert_eq!(0, contract.get_post(0).unwrap().get_total_donation(&None));
+    }
+
+    #[test]
+    #[should_panic(expected = "Attached deposit must be greater than 0")]
+    fn donation_requires_deposit() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+
+        contract.donate(0, "Support Trump for the USA".to_string());
+    }
+
+    #[test]
+    fn edit_post_keeps_revisions() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+        let mut context = get_caller_context("alice_near", 0);
+        context.block_timestamp = 10;
+        testing_env!(context);
+        contract.edit_post(0, "This is the new title".to_string(), "Lets go!".to_string(), None, None);
+        let mut context = get_caller_context("alice_near", 0);
+        context.block_timestamp = 20;
+        testing_env!(context);
+        contract.edit_post(0, "This is the final title".to_string(), "Lets go again!".to_string(), None, None);
+
+        let post = contract.get_post(0).unwrap();
+        assert_eq!("This is the final title".to_string(), post.get_title());
+        assert_eq!("Lets go again!".to_string(), post.get_body());
+
+        // every prior version is kept, oldest first
+        let revisions = contract.get_post_revisions(0, None, 10);
+        assert_eq!(2, revisions.len());
+        assert_eq!("This is the title".to_string(), revisions[0].get_title());
+        assert_eq!("Lets go!".to_string(), revisions[1].get_body());
+        assert_eq!("alice_near".to_string(), revisions[1].get_editor());
+
+        // each revision carries the time its own version was written
+        assert_eq!(0, revisions[0].get_created_at());
+        assert_eq!(10, revisions[1].get_created_at());
+        assert_eq!(20, post.get_updated_at());
+
+        assert_eq!(1, contract.get_post_revision(0, 1).unwrap().get_revision_id());
+        assert!(contract.get_post_revision(0, 2).is_none());
+        assert_eq!(1, contract.get_post_revisions(0, Some(0), 10)[0].get_revision_id());
+    }
+
+    #[test]
+    fn only_the_latest_revisions_are_kept() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+
+        for i in 0..=MAX_REVISIONS_PER_POST {
+            testing_env!(get_caller_context("alice_near", 0));
+            contract.edit_post(0, format!("This is the title {}", i), "Lets go Brandon!".to_string(), None, None);
+        }
+
+        // the first revision made room for the last one
+        assert!(contract.get_post_revision(0, 0).is_none());
+        assert_eq!(1, contract.get_post_revisions(0, None, 1)[0].get_revision_id());
+        let last = contract.get_post_revisions(0, Some(MAX_REVISIONS_PER_POST - 1), 10);
+        assert_eq!(vec![MAX_REVISIONS_PER_POST], last.iter().map(|revision| revision.get_revision_id()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn delete_post_drops_its_revisions() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+        contract.edit_post(0, "This is the new title".to_string(), "Lets go!".to_string(), None, None);
+
+        let account_id: ValidAccountId = "alice_near".to_string().try_into().unwrap();
+        let available = contract.storage_balance_of(account_id.clone()).unwrap().available.0;
+        contract.delete_post(0, None);
+
+        assert!(contract.get_post_revisions(0, None, 10).is_empty());
+        assert!(contract.get_post_revision(0, 0).is_none());
+        assert!(contract.storage_balance_of(account_id).unwrap().available.0 > available);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the author can edit this post")]
+    fn edit_post_by_other_account() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+
+        testing_env!(get_caller_context("bob_near", 0));
+        contract.edit_post(0, "This is the new title".to_string(), "Lets go!".to_string(), None, None);
+    }
+
+
+    #[test]
+    fn threaded_comments() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+        let first = contract.create_comment(0, "This is the first comment".to_string(), None);
+        let second = contract.create_comment(0, "This is the second comment".to_string(), None);
+        let reply = contract.create_comment(0, "This is a reply to the first".to_string(), Some(first));
+        contract.create_comment(0, "This is a reply to the reply".to_string(), Some(reply));
+
+        assert_eq!(Some(first), contract.get_comment(reply).get_parent_id());
+        assert_eq!(vec![reply], contract.get_comment(first).get_children());
+        assert_eq!(2, contract.get_comment(3).get_depth());
+
+        let ids = |comments: Vec<Comment>| comments.iter().map(|comment| comment.get_comment_id()).collect::<Vec<usize>>();
+
+        // threads are returned depth first, pages go on after the last comment of the previous one
+        assert_eq!(vec![first, reply, 3, second], ids(contract.get_post_comment_threads(0, None, 10)));
+        assert_eq!(vec![3, second], ids(contract.get_post_comment_threads(0, Some(reply), 2)));
+        assert_eq!(vec![first, reply], ids(contract.get_comment_thread(first, None, 2)));
+        assert_eq!(vec![3], ids(contract.get_comment_thread(first, Some(reply), 2)));
+
+        // a deleted reply stays while it has replies, a page after a removed one still goes on
+        contract.delete_comment(0, reply, None);
+        assert_eq!(vec![first, reply, 3, second], ids(contract.get_post_comment_threads(0, None, 10)));
+        contract.delete_comment(0, 3, None);
+        assert_eq!(vec![first, second], ids(contract.get_post_comment_threads(0, None, 10)));
+        assert_eq!(vec![second], ids(contract.get_post_comment_threads(0, Some(3), 10)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Maximum reply depth reached")]
+    fn reply_depth_is_limited() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+        let mut parent_id = contract.create_comment(0, "This is the comment".to_string(), None);
+
+        for _ in 0..=contract.get_config().max_comment_depth {
+            parent_id = contract.create_comment(0, "This is a reply".to_string(), Some(parent_id));
+        }
+    }
+
+
+    #[test]
+    fn ft_donation() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+
+        // the token contract calls back with the donation message
+        testing_env!(get_caller_context("usdc.testnet", 0));
+        let result = contract.ft_on_transfer(
+            "bob_near".to_string().try_into().unwrap(),
+            U128(500),
+            r#"{"post_id": 0, "message": "Keep writing"}"#.to_string(),
+        );
+        assert!(matches!(result, PromiseOrValue::Promise(_)));
+
+        testing_env_with_promise_results(get_caller_context("decentrablog.npmrunstart.testnet", 0), PromiseResult::Successful(vec![]));
+        let unused = contract.on_ft_donation_transferred(0, "bob_near".to_string(), "usdc.testnet".to_string(), U128(500), "Keep writing".to_string());
+        assert_eq!(0, unused.0);
+
+        // totals are kept per token
+        assert_eq!(500, contract.get_post_total_donation(0, Some("usdc.testnet".to_string())).0);
+        assert_eq!(0, contract.get_post_total_donation(0, Some("dai.testnet".to_string())).0);
+        assert_eq!(0, contract.get_post_total_donation(0, None).0);
+    }
+
+    #[test]
+    fn failed_ft_donation_is_refunded() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+
+        testing_env_with_promise_results(get_caller_context("decentrablog.npmrunstart.testnet", 0), PromiseResult::Failed);
+        let unused = contract.on_ft_donation_transferred(0, "bob_near".to_string(), "usdc.testnet".to_string(), U128(500), "Keep writing".to_string());
+
+        assert_eq!(500, unused.0);
+        assert_eq!(0, contract.get_post_total_donation(0, Some("usdc.testnet".to_string())).0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid donation message")]
+    fn ft_donation_with_invalid_message() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+
+        contract.ft_on_transfer("bob_near".to_string().try_into().unwrap(), U128(500), "post 0".to_string());
+    }
+
+
+    #[test]
+    fn state_changes_emit_events() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+        assert_eq!(
+            vec![r#"EVENT_JSON:{"standard":"decentrablog","version":"1.0.0","event":"post_created","data":{"post_id":0,"author":"alice_near","title":"This is the title"}}"#.to_string()],
+            get_logs()
+        );
+
+        contract.downvote(0);
+        assert_eq!(
+            r#"EVENT_JSON:{"standard":"decentrablog","version":"1.0.0","event":"post_voted","data":{"post_id":0,"voter":"alice_near","status":"Downvoted"}}"#.to_string(),
+            get_logs()[1]
+        );
+
+        contract.create_comment(0, "This is the comment".to_string(), None);
+        assert_eq!(
+            r#"EVENT_JSON:{"standard":"decentrablog","version":"1.0.0","event":"comment_created","data":{"comment_id":0,"post_id":0,"parent_id":null,"author":"alice_near"}}"#.to_string(),
+            get_logs()[2]
+        );
+    }
+
+
+    #[test]
+    fn moderators_can_delete() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+
+        // the owner appoints an admin, who appoints a moderator
+        contract.grant_role("bob_near".to_string().try_into().unwrap(), Role::Admin);
+        testing_env!(get_caller_context("bob_near", 0));
+        contract.grant_role("carol_near".to_string().try_into().unwrap(), Role::Moderator);
+
+        assert_eq!(Some(Role::Owner), contract.get_role("alice_near".to_string()));
+        assert_eq!(vec!["carol_near".to_string()], contract.get_role_members(Role::Moderator));
+
+        testing_env!(get_caller_context("carol_near", 0));
+        contract.delete_post(0, None);
+        assert!(contract.get_post(0).unwrap().is_deleted());
+
+        // once revoked the moderator can no longer delete
+        testing_env!(get_caller_context("bob_near", 0));
+        contract.revoke_role("carol_near".to_string().try_into().unwrap());
+        assert_eq!(None, contract.get_role("carol_near".to_string()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the author or a moderator can delete this post")]
+    fn delete_post_without_role() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+
+        testing_env!(get_caller_context("bob_near", 0));
+        contract.delete_post(0, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Not allowed to grant this role")]
+    fn admin_cannot_grant_admin() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+        contract.grant_role("bob_near".to_string().try_into().unwrap(), Role::Admin);
+
+        testing_env!(get_caller_context("bob_near", 0));
+        contract.grant_role("carol_near".to_string().try_into().unwrap(), Role::Admin);
+    }
+
+
+    #[test]
+    fn transfer_ownership() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+
+        contract.propose_owner("dao_near".to_string().try_into().unwrap());
+        assert_eq!(Some("dao_near".to_string()), contract.get_proposed_owner());
+        assert_eq!("alice_near".to_string(), contract.get_owner());
+
+        testing_env!(get_caller_context("dao_near", 0));
+        contract.accept_ownership();
+        assert_eq!("dao_near".to_string(), contract.get_owner());
+        assert_eq!(None, contract.get_proposed_owner());
+        assert_eq!(None, contract.get_role("alice_near".to_string()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the proposed owner can accept ownership")]
+    fn cancelled_proposal_cannot_be_accepted() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+
+        contract.propose_owner("dao_near".to_string().try_into().unwrap());
+        contract.cancel_owner_proposal();
+
+        testing_env!(get_caller_context("dao_near", 0));
+        contract.accept_ownership();
+    }
+
+
+    #[test]
+    fn migrate_from_v1() {
+        use std::collections::HashSet;
+        use legacy::{CommentV1, DonationLogV1};
+
+        // the old posts were written before the migration
+        let mut context = get_context(vec![], false);
+        context.block_timestamp = 100;
+        testing_env!(context);
+
+        // write the state the way the first version of the contract did
+        let mut old = BlogV1 {
+            owner: "alice_near".to_string(),
+            user_posts: UnorderedMap::new(b"user_posts".to_vec()),
+            posts: UnorderedMap::new(b"posts".to_vec()),
+            comments: UnorderedMap::new(b"comments".to_vec()),
+            next_post_id: 2,
+            next_comment_id: 2,
+            next_donation_id: 1,
+        };
+        let mut upvotes = HashSet::new();
+        upvotes.insert("bob_near".to_string());
+        old.posts.insert(&1, &PostV1 {
+            post_id: 1,
+            title: "This is the title".to_string(),
+            body: "Lets go Brandon!".to_string(),
+            author: "alice_near".to_string(),
+            created_at: 42,
+            comments: vec![1],
+            upvotes,
+            downvotes: HashSet::new(),
+            donation_logs: vec![DonationLogV1 {
+                donation_id: 0,
+                amount: 1000000,
+                donor: "bob_near".to_string(),
+                created_at: 43,
+                message: "Support Trump for the USA".to_string(),
+                post_id: 1,
+            }],
+        });
+        old.user_posts.insert(&"alice_near".to_string(), &vec![0, 1]);
+        // comment 0 belonged to the deleted post 0
+        old.comments.insert(&0, &CommentV1 {
+            comment_id: 0,
+            body: "This is an orphan".to_string(),
+            author: "bob_near".to_string(),
+            created_at: 40,
+        });
+        old.comments.insert(&1, &CommentV1 {
+            comment_id: 1,
+            body: "This is the comment".to_string(),
+            author: "bob_near".to_string(),
+            created_at: 44,
+        });
+        env::state_write(&old);
+
+        let mut contract = Blog::migrate();
+        assert!(contract.is_migrating());
+        assert!(contract.migrate_batch(MAX_MIGRATION_BATCH));
+        assert!(!contract.is_migrating());
+        register_accounts(&mut contract);
+        assert_eq!(STATE_VERSION, contract.get_state_version());
+        assert_eq!("alice_near".to_string(), contract.get_owner());
+        // the deleted post 0 is dropped from the posts of its author
+        assert_eq!(vec![1], contract.get_user_posts("alice_near".to_string()).iter().map(|post| post.get_post_id()).collect::<Vec<_>>());
+
+        let post = contract.get_post(1).unwrap();
+        assert_eq!("This is the title".to_string(), post.get_title());
+        assert_eq!(42, post.get_updated_at());
+        assert_eq!(1000000, post.get_total_donation(&None));
+        assert_eq!((1, 0), contract.get_votes_statistics(1));
+        assert_eq!(VoteStatus::Upvoted, contract.get_user_vote_status(1, "bob_near".to_string()));
+
+        assert_eq!(1, contract.get_total_comments());
+        assert_eq!(1, contract.get_comment(1).get_post_id());
+        assert_eq!(1, contract.get_posts_by_cursor(None, 10, None)[0].get_post_id());
+
+        // the migrated state keeps working with the new methods
+        contract.edit_post(1, "This is the new title".to_string(), "Lets go!".to_string(), None, None);
+        contract.create_comment(1, "This is a reply".to_string(), Some(1));
+        assert_eq!(2, contract.get_next_post_id());
+        assert_eq!(1, contract.get_post_revisions(1, None, 10).len());
+    }
+
+    #[test]
+    #[should_panic(expected = "Only owner can migrate the contract")]
+    fn migrate_by_other_account() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        env::state_write(&BlogV1 {
+            owner: "alice_near".to_string(),
+            user_posts: UnorderedMap::new(b"user_posts".to_vec()),
+            posts: UnorderedMap::new(b"posts".to_vec()),
+            comments: UnorderedMap::new(b"comments".to_vec()),
+            next_post_id: 0,
+            next_comment_id: 0,
+            next_donation_id: 0,
+        });
+
+        testing_env!(get_caller_context("bob_near", 0));
+        Blog::migrate();
+    }
+
+    #[test]
+    fn migrate_from_v1_in_batches() {
+        use std::collections::HashSet;
+
+        let mut context = get_context(vec![], false);
+        context.block_timestamp = 100;
+        testing_env!(context);
+        let mut old = BlogV1 {
+            owner: "alice_near".to_string(),
+            user_posts: UnorderedMap::new(b"user_posts".to_vec()),
+            posts: UnorderedMap::new(b"posts".to_vec()),
+            comments: UnorderedMap::new(b"comments".to_vec()),
+            next_post_id: 3,
+            next_comment_id: 0,
+            next_donation_id: 0,
+        };
+        for post_id in 0..3 {
+            old.posts.insert(&post_id, &PostV1 {
+                post_id,
+                title: "This is the title".to_string(),
+                body: "Lets go Brandon!".to_string(),
+                author: "alice_near".to_string(),
+                created_at: post_id as u64,
+                comments: vec![],
+                upvotes: HashSet::new(),
+                downvotes: HashSet::new(),
+                donation_logs: vec![],
+            });
+        }
+        old.user_posts.insert(&"alice_near".to_string(), &vec![0, 1, 2]);
+        env::state_write(&old);
+
+        let mut contract = Blog::migrate();
+        // one call per post, then one for the list of posts of alice
+        for _ in 0..3 {
+            assert!(!contract.migrate_batch(1));
+        }
+        assert_eq!(3, contract.get_total_posts());
+        assert!(contract.migrate_batch(1));
+        assert!(contract.migrate_batch(1));
+
+        register_accounts(&mut contract);
+        assert_eq!(vec![2, 1, 0], contract.get_posts_by_cursor(None, 10, None).iter().map(|post| post.get_post_id()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn migrate_large_post_over_batches() {
+        use legacy::CommentV1;
+
+        let mut context = get_context(vec![], false);
+        context.block_timestamp = 100;
+        testing_env!(context);
+        let mut old = BlogV1 {
+            owner: "alice_near".to_string(),
+            user_posts: UnorderedMap::new(b"user_posts".to_vec()),
+            posts: UnorderedMap::new(b"posts".to_vec()),
+            comments: UnorderedMap::new(b"comments".to_vec()),
+            next_post_id: 1,
+            next_comment_id: 3,
+            next_donation_id: 0,
+        };
+        for comment_id in 0..3 {
+            old.comments.insert(&comment_id, &CommentV1 {
+                comment_id,
+                body: "This is the comment".to_string(),
+                author: "bob_near".to_string(),
+                created_at: 1,
+            });
+        }
+        old.posts.insert(&0, &PostV1 {
+            post_id: 0,
+            title: "This is the title".to_string(),
+            body: "Lets go Brandon!".to_string(),
+            author: "alice_near".to_string(),
+            created_at: 0,
+            comments: vec![0, 1, 2],
+            upvotes: vec!["bob_near".to_string(), "carol_near".to_string()].into_iter().collect(),
+            downvotes: vec!["dave_near".to_string()].into_iter().collect(),
+            donation_logs: vec![],
+        });
+        old.user_posts.insert(&"alice_near".to_string(), &vec![0]);
+        env::state_write(&old);
+
+        // the 3 comments, 3 votes and the post itself take 7 of the batch
+        let mut contract = Blog::migrate();
+        assert!(!contract.migrate_batch(4));
+        assert_eq!(3, contract.get_total_comments());
+        assert_eq!(0, contract.get_total_posts());
+        assert!(contract.migrate_batch(4));
+
+        register_accounts(&mut contract);
+        assert_eq!((2, 1), contract.get_votes_statistics(0));
+        assert_eq!(VoteStatus::Downvoted, contract.get_user_vote_status(0, "dave_near".to_string()));
+        assert_eq!(3, contract.get_post_comment_threads(0, None, 10).len());
+        assert_eq!(1, contract.get_total_posts());
+    }
+
+    #[test]
+    #[should_panic(expected = "State is already at version 2")]
+    fn migrate_twice() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        env::state_write(&BlogV1 {
+            owner: "alice_near".to_string(),
+            user_posts: UnorderedMap::new(b"user_posts".to_vec()),
+            posts: UnorderedMap::new(b"posts".to_vec()),
+            comments: UnorderedMap::new(b"comments".to_vec()),
+            next_post_id: 0,
+            next_comment_id: 0,
+            next_donation_id: 0,
+        });
+
+        let contract = Blog::migrate();
+        env::state_write(&contract);
+        Blog::migrate();
+    }
+
+
+    #[test]
+    fn votes_are_counted_per_account() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+
+        contract.upvote(0);
+        testing_env!(get_caller_context("bob_near", 0));
+        contract.upvote(0);
+        testing_env!(get_caller_context("carol_near", 0));
+        contract.downvote(0);
+        assert_eq!((2, 1), contract.get_votes_statistics(0));
+
+        // removing an upvote leaves a downvote untouched
+        contract.remove_upvote(0);
+        assert_eq!(VoteStatus::Downvoted, contract.get_user_vote_status(0, "carol_near".to_string()));
+
+        // switching sides moves the vote between counters
+        contract.upvote(0);
+        assert_eq!((3, 0), contract.get_votes_statistics(0));
+
+        contract.remove_upvote(0);
+        assert_eq!(VoteStatus::None, contract.get_user_vote_status(0, "carol_near".to_string()));
+        assert_eq!((2, 0), contract.get_votes_statistics(0));
+    }
+
+
+    #[test]
+    fn storage_is_charged_to_the_author() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+
+        let before = contract.storage_balance_of("alice_near".to_string().try_into().unwrap()).unwrap();
+        assert_eq!(ONE_NEAR, before.total.0);
+        assert_eq!(ONE_NEAR - contract.storage_balance_bounds().min.0, before.available.0);
+
+        let storage_usage = env::storage_usage();
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+        let used = u128::from(env::storage_usage() - storage_usage) * env::storage_byte_cost();
+
+        let after = contract.storage_balance_of("alice_near".to_string().try_into().unwrap()).unwrap();
+        assert_eq!(before.available.0 - used, after.available.0);
+
+        // deleting the post gives its storage back
+        contract.delete_post(0, None);
+        let deleted = contract.storage_balance_of("alice_near".to_string().try_into().unwrap()).unwrap();
+        assert!(deleted.available.0 > after.available.0);
+
+        testing_env!(get_caller_context("alice_near", 1));
+        contract.storage_withdraw(Some(U128(1000)));
+        assert_eq!(
+            ONE_NEAR - 1000,
+            contract.storage_balance_of("alice_near".to_string().try_into().unwrap()).unwrap().total.0
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Account dave_near is not registered, call storage_deposit first")]
+    fn unregistered_account_cannot_post() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+
+        testing_env!(get_caller_context("dave_near", 0));
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+    }
+
+    #[test]
+    fn registration_fits_in_the_minimum_balance() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        // the first registration also records the state version of the new blog
+        testing_env!(get_caller_context("bob_near", contract.storage_balance_bounds().min.0));
+        contract.storage_deposit(None, Some(true));
+
+        let account_id = "a".repeat(64);
+        let storage_usage = env::storage_usage();
+        testing_env!(get_caller_context(&account_id, contract.storage_balance_bounds().min.0));
+        contract.storage_deposit(None, Some(true));
+
+        assert!(env::storage_usage() - storage_usage <= STORAGE_REGISTRATION_BYTES);
+    }
+
+    #[test]
+    #[should_panic(expected = "Can't unregister an account that still has posts, comments or votes")]
+    fn unregister_with_content() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+
+        testing_env!(get_caller_context("alice_near", 1));
+        contract.storage_unregister(None);
+    }
+
+    #[test]
+    fn content_kept_by_force_unregister_is_not_credited() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+
+        testing_env!(get_caller_context("bob_near", 0));
+        let comment_id = contract.create_comment(0, "This comment is a lot longer than the tombstone it leaves behind".to_string(), None);
+        testing_env!(get_caller_context("bob_near", 1));
+        assert!(contract.storage_unregister(Some(true)));
+
+        // bob registers again, deleting the old comment doesn't pay for the new one
+        testing_env!(get_caller_context("bob_near", 10u128.pow(24)));
+        contract.storage_deposit(None, None);
+        testing_env!(get_caller_context("bob_near", 0));
+        contract.create_comment(0, "This is a new comment".to_string(), None);
+
+        let account_id: ValidAccountId = "bob_near".to_string().try_into().unwrap();
+        let available = contract.storage_balance_of(account_id.clone()).unwrap().available.0;
+        contract.delete_comment(0, comment_id, None);
+        assert_eq!(available, contract.storage_balance_of(account_id).unwrap().available.0);
+    }
+
+
+    #[test]
+    fn cursor_paging_is_stable_after_deletes() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+
+        for i in 0..10 {
+            contract.create_post(format!("This is the title {}", i), format!("Lets go Brandon! {}", i), None, None, None);
+        }
+        contract.delete_post(2, None);
+
+        let ids = |posts: Vec<Post>| posts.iter().map(|post| post.get_post_id()).collect::<Vec<usize>>();
+
+        // newest first by default
+        assert_eq!(vec![9, 8, 7], ids(contract.get_posts_by_cursor(None, 3, None)));
+        assert_eq!(vec![6, 5, 4], ids(contract.get_posts_by_cursor(Some(7), 3, None)));
+        assert_eq!(vec![3, 1, 0], ids(contract.get_posts_by_cursor(Some(4), 3, None)));
+
+        assert_eq!(vec![0, 1, 3], ids(contract.get_posts_by_cursor(None, 3, Some(SortOrder::Ascending))));
+        assert_eq!(vec![4, 5], ids(contract.get_posts_by_cursor(Some(3), 2, Some(SortOrder::Ascending))));
+        // a deleted post still works as a cursor
+        assert_eq!(vec![3, 4], ids(contract.get_posts_by_cursor(Some(2), 2, Some(SortOrder::Ascending))));
+        assert_eq!(vec![1, 0], ids(contract.get_posts_by_cursor(Some(2), 2, None)));
+
+        // the page based listing follows the same order
+        assert_eq!(vec![0, 1, 3, 4], ids(contract.get_paging_posts(1, 4)));
+        assert_eq!(ids(contract.get_posts_by_cursor(Some(4), 4, Some(SortOrder::Ascending))), ids(contract.get_paging_posts(2, 4)));
+    }
+
+    #[test]
+    #[should_panic(expected = "At most 100 posts can be listed at once")]
+    fn cursor_paging_is_bounded() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let contract = Blog::default();
+
+        contract.get_posts_by_cursor(None, MAX_POSTS_PER_CALL + 1, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "At most 100 tags can be listed at once")]
+    fn top_tags_are_bounded() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let contract = Blog::default();
+
+        contract.get_top_tags(MAX_TAGS_PER_CALL + 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the first 1000 posts can be paged, use get_posts_by_cursor")]
+    fn paging_posts_is_bounded() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let contract = Blog::default();
+
+        contract.get_paging_posts(11, 100);
+    }
+
+
+    #[test]
+    fn posts_are_indexed_by_tag() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), Some(vec!["Rust Lang".to_string(), "near".to_string()]), None, None);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), Some(vec!["NEAR".to_string(), "near".to_string()]), None, None);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+
+        assert_eq!(vec!["rust-lang".to_string(), "near".to_string()], contract.get_post(0).unwrap().get_tags());
+        assert_eq!(vec!["near".to_string()], contract.get_post(1).unwrap().get_tags());
+
+        let ids = |posts: Vec<Post>| posts.iter().map(|post| post.get_post_id()).collect::<Vec<usize>>();
+        assert_eq!(vec![1, 0], ids(contract.get_posts_by_tag("Near".to_string(), None, 10)));
+        assert_eq!(vec![0], ids(contract.get_posts_by_tag("near".to_string(), Some(1), 1)));
+        assert!(contract.get_posts_by_tag("near".to_string(), Some(0), 10).is_empty());
+
+        // editing moves the post between tags, the revision keeps the old ones
+        contract.edit_post(0, "This is the title".to_string(), "Lets go Brandon!".to_string(), Some(vec!["web3".to_string()]), None);
+        assert_eq!(vec![1], ids(contract.get_posts_by_tag("near".to_string(), None, 10)));
+        assert_eq!(vec!["rust-lang".to_string(), "near".to_string()], contract.get_post_revision(0, 0).unwrap().get_tags());
+
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), Some(vec!["web3".to_string()]), None, None);
+        assert_eq!(
+            vec![("web3".to_string(), 2), ("near".to_string(), 1)],
+            contract.get_top_tags(10)
+        );
+
+        // deleted posts leave the index
+        contract.delete_post(1, None);
+        assert_eq!(vec![("web3".to_string(), 2)], contract.get_top_tags(10));
+
+        // tags used as often are ordered by name
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), Some(vec!["zeta".to_string(), "alpha".to_string()]), None, None);
+        assert_eq!(
+            vec![("web3".to_string(), 2), ("alpha".to_string(), 1)],
+            contract.get_top_tags(2)
+        );
+        assert_eq!(vec![4], ids(contract.get_posts_by_tag("zeta".to_string(), None, 10)));
+    }
+
+    #[test]
+    #[should_panic(expected = "A post can have at most 5 tags")]
+    fn too_many_tags() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+
+        let tags = (0..6).map(|i| format!("tag{}", i)).collect();
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), Some(tags), None, None);
+    }
+
+
+    #[test]
+    fn authors_delete_their_posts_and_comments() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), Some(vec!["near".to_string()]), None, None);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+
+        testing_env!(get_caller_context("bob_near", 0));
+        let comment_id = contract.create_comment(1, "This is a comment".to_string(), None);
+        let reply_id = contract.create_comment(1, "This is a reply".to_string(), Some(comment_id));
+        contract.create_comment(0, "This is a comment".to_string(), None);
+
+        // the deleted comment stays in its thread as long as it has replies
+        contract.delete_comment(1, comment_id, Some("Off topic".to_string()));
+        let comment = contract.get_comment(comment_id);
+        assert!(comment.get_body().is_empty());
+        assert_eq!("bob_near".to_string(), comment.get_tombstone().unwrap().get_deleted_by());
+        assert_eq!(Some("Off topic".to_string()), comment.get_tombstone().unwrap().get_reason());
+        assert_eq!(1, contract.get_comments(1).len());
+        assert_eq!(1, contract.get_post_total_comments(1));
+        assert_eq!(2, contract.get_post_comment_threads(1, None, 10).len());
+
+        contract.delete_comment(1, reply_id, None);
+        assert!(contract.get_post_comment_threads(1, None, 10).is_empty());
+        assert!(contract.get_comment_thread(comment_id, None, 10).is_empty());
+
+        testing_env!(get_caller_context("alice_near", 0));
+        contract.delete_post(0, Some("Outdated".to_string()));
+
+        let post = contract.get_post(0).unwrap();
+        assert!(post.get_title().is_empty());
+        assert_eq!(Some("Outdated".to_string()), post.get_tombstone().unwrap().get_reason());
+        // the comments of the post read as deleted with it
+        assert!(contract.get_comment(2).is_deleted());
+        assert!(contract.get_comment(2).get_body().is_empty());
+        assert!(contract.get_comments(0).is_empty());
+        assert_eq!(0, contract.get_post_total_comments(0));
+
+        assert_eq!(1, contract.get_total_posts());
+        assert_eq!(1, contract.get_posts().len());
+        assert_eq!(1, contract.get_user_posts("alice_near".to_string()).len());
+        assert!(contract.get_posts_by_tag("near".to_string(), None, 10).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Reason must be at most 280 bytes long")]
+    fn delete_reason_is_bounded() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+
+        contract.delete_post(0, Some("a".repeat(281)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the author or a moderator can delete this comment")]
+    fn delete_comment_of_someone_else() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+        contract.create_comment(0, "This is a comment".to_string(), None);
+
+        testing_env!(get_caller_context("bob_near", 0));
+        contract.delete_comment(0, 0, None);
+    }
+
+    #[test]
+    fn unpublished_posts_are_not_listed() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), Some(vec!["near".to_string()]), None, None);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+
+        contract.unpublish_post(0);
+        assert_eq!(PostStatus::Draft, contract.get_post(0).unwrap().get_status());
+        assert_eq!(1, contract.get_total_posts());
+        assert_eq!(1, contract.get_paging_posts(1, 10).len());
+        assert_eq!(1, contract.get_user_posts("alice_near".to_string()).len());
+        assert!(contract.get_top_tags(10).is_empty());
+
+        contract.publish_post(0, None);
+        assert_eq!(2, contract.get_total_posts());
+        assert_eq!(vec![("near".to_string(), 1)], contract.get_top_tags(10));
+    }
+
+    #[test]
+    #[should_panic(expected = "Post is deleted or not published")]
+    fn vote_on_unpublished_post() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+        contract.unpublish_post(0);
+
+        testing_env!(get_caller_context("bob_near", 0));
+        contract.upvote(0);
+    }
+
+
+    #[test]
+    fn drafts_and_scheduled_posts() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, Some(PostStatus::Draft), None);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), Some(vec!["near".to_string()]), Some(PostStatus::Scheduled { publish_at: 1000 }), None);
+
+        let ids = |posts: Vec<Post>| posts.iter().map(|post| post.get_post_id()).collect::<Vec<usize>>();
+        assert_eq!(vec![0], ids(contract.get_posts()));
+        assert_eq!(vec![0], ids(contract.get_paging_posts(1, 10)));
+        assert_eq!(vec![0], ids(contract.get_posts_by_cursor(None, 10, None)));
+        assert_eq!(vec![0], ids(contract.get_user_posts("alice_near".to_string())));
+        assert_eq!(vec![1, 2], ids(contract.get_user_drafts("alice_near".to_string())));
+        assert_eq!(1, contract.get_total_posts());
+        assert!(contract.get_posts_by_tag("near".to_string(), None, 10).is_empty());
+
+        // the scheduled post shows up once its time has passed
+        let mut context = get_caller_context("alice_near", 0);
+        context.block_timestamp = 1000;
+        testing_env!(context);
+        assert_eq!(vec![0, 2], ids(contract.get_paging_posts(1, 10)));
+        assert_eq!(vec![2, 0], ids(contract.get_posts_by_cursor(None, 10, None)));
+        assert_eq!(vec![0, 2], ids(contract.get_user_posts("alice_near".to_string())));
+        assert_eq!(vec![2], ids(contract.get_posts_by_tag("near".to_string(), None, 10)));
+        assert_eq!(2, contract.get_total_posts());
+
+        contract.publish_post(1, Some(2000));
+        assert_eq!(PostStatus::Scheduled { publish_at: 2000 }, contract.get_post(1).unwrap().get_status());
+        assert_eq!(2, contract.get_total_posts());
+        assert_eq!(vec![1], ids(contract.get_user_drafts("alice_near".to_string())));
+
+        contract.publish_post(1, None);
+        assert_eq!(3, contract.get_total_posts());
+        assert!(contract.get_user_drafts("alice_near".to_string()).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Scheduled time must be in the future")]
+    fn schedule_in_the_past() {
+        let mut context = get_context(vec![], false);
+        context.block_timestamp = 1000;
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, Some(PostStatus::Scheduled { publish_at: 1000 }), None);
+    }
+
+
+    #[test]
+    fn profiles_resolve_in_batch() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+
+        contract.set_profile(" Alice ".to_string(), Some("Writes about NEAR".to_string()), None, Some(vec!["https://alice.dev".to_string()]));
+        testing_env!(get_caller_context("bob_near", 0));
+        contract.set_profile("Bob".to_string(), None, Some("bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi".to_string()), None);
+
+        let profile = contract.get_profile("alice_near".to_string()).unwrap();
+        assert_eq!("Alice".to_string(), profile.get_display_name());
+        assert_eq!(vec!["https://alice.dev".to_string()], profile.get_links());
+
+        let profiles = contract.get_profiles(vec!["alice_near".to_string(), "bob_near".to_string(), "carol_near".to_string()]);
+        assert_eq!(2, profiles.len());
+        assert_eq!("Bob".to_string(), profiles["bob_near"].get_display_name());
+        assert!(!profiles.contains_key("carol_near"));
+
+        contract.remove_profile();
+        assert!(contract.get_profile("bob_near".to_string()).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Display name must be at most 64 bytes long")]
+    fn display_name_too_long() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+
+        contract.set_profile("a".repeat(65), None, None, None);
+    }
+
+
+    #[test]
+    fn feed_merges_followed_authors() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+
+        for account_id in ["alice_near", "bob_near", "carol_near", "alice_near", "bob_near"].iter() {
+            testing_env!(get_caller_context(account_id, 0));
+            contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+        }
+        testing_env!(get_caller_context("bob_near", 0));
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, Some(PostStatus::Draft), None);
+
+        testing_env!(get_caller_context("carol_near", 0));
+        contract.follow("alice_near".to_string().try_into().unwrap());
+        contract.follow("bob_near".to_string().try_into().unwrap());
+        testing_env!(get_caller_context("bob_near", 0));
+        contract.follow("alice_near".to_string().try_into().unwrap());
+
+        assert_eq!((2, 0), contract.get_follow_statistics("alice_near".to_string()));
+        assert_eq!((0, 2), contract.get_follow_statistics("carol_near".to_string()));
+        assert_eq!(vec!["bob_near".to_string()], contract.get_followers("alice_near".to_string(), None, 1));
+        assert_eq!(vec!["carol_near".to_string()], contract.get_followers("alice_near".to_string(), Some("bob_near".to_string()), 10));
+        assert_eq!(vec!["alice_near".to_string(), "bob_near".to_string()], contract.get_following("carol_near".to_string(), None, 10));
+        assert!(contract.is_following("carol_near".to_string(), "bob_near".to_string()));
+
+        let ids = |page: &FeedPage| page.posts.iter().map(|post| post.get_post_id()).collect::<Vec<usize>>();
+        let page = contract.get_feed("carol_near".to_string(), None, 3);
+        assert_eq!(vec![4, 3, 1], ids(&page));
+        assert_eq!(Some(FeedCursor { after_author: None, from_post_id: Some(1) }), page.next);
+        let page = contract.get_feed("carol_near".to_string(), page.next, 3);
+        assert_eq!(vec![0], ids(&page));
+        assert_eq!(None, page.next);
+
+        testing_env!(get_caller_context("carol_near", 0));
+        contract.unfollow("bob_near".to_string().try_into().unwrap());
+        assert_eq!(vec![3, 0], ids(&contract.get_feed("carol_near".to_string(), None, 10)));
+        assert_eq!((0, 1), contract.get_follow_statistics("bob_near".to_string()));
+    }
+
+    #[test]
+    fn feed_reads_a_bounded_number_of_authors() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+
+        testing_env!(get_caller_context("carol_near", 0));
+        // the followed accounts are read by account id, these come before alice
+        for i in 0..MAX_FEED_AUTHORS {
+            testing_env!(get_caller_context("carol_near", 0));
+            contract.follow(format!("aa_author_{:02}", i).try_into().unwrap());
+        }
+        contract.follow("alice_near".to_string().try_into().unwrap());
+
+        let page = contract.get_feed("carol_near".to_string(), None, 10);
+        assert!(page.posts.is_empty());
+        assert_eq!(Some(FeedCursor { after_author: Some(format!("aa_author_{:02}", MAX_FEED_AUTHORS - 1)), from_post_id: None }), page.next);
+
+        let page = contract.get_feed("carol_near".to_string(), page.next, 10);
+        assert_eq!(0, page.posts[0].get_post_id());
+        assert_eq!(None, page.next);
+    }
+
+    #[test]
+    #[should_panic(expected = "Already following this account")]
+    fn follow_twice() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+
+        contract.follow("bob_near".to_string().try_into().unwrap());
+        contract.follow("bob_near".to_string().try_into().unwrap());
+    }
+
+
+    #[test]
+    fn downvoted_posts_have_a_negative_score() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+
+        for account_id in ["bob_near", "carol_near"].iter() {
+            testing_env!(get_caller_context(account_id, 0));
+            contract.downvote(0);
+        }
+
+        assert_eq!(-2, contract.get_post(0).unwrap().get_score());
+    }
+
+    #[test]
+    fn posts_are_ranked() {
+        let mut context = get_context(vec![], false);
+        context.block_timestamp = 1_000_000_000_000;
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+
+        for _ in 0..3 {
+            contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+        }
+
+        // post 0: +2, post 1: +1 -1, post 2: -1
+        testing_env!(get_caller_context("bob_near", 0));
+        contract.upvote(0);
+        contract.upvote(1);
+        contract.downvote(2);
+        testing_env!(get_caller_context("carol_near", 0));
+        contract.upvote(0);
+        contract.downvote(1);
+
+        let ids = |posts: Vec<Post>| posts.iter().map(|post| post.get_post_id()).collect::<Vec<usize>>();
+        assert_eq!(vec![0, 1, 2], ids(contract.get_ranked_posts(Ranking::Top, None, 1, 10)));
+        assert_eq!(vec![0], ids(contract.get_ranked_posts(Ranking::Hot, None, 1, 1)));
+        assert_eq!(vec![1], ids(contract.get_ranked_posts(Ranking::Controversial, None, 1, 1)));
+
+        // votes move the post in the index
+        contract.remove_downvote(1);
+        testing_env!(get_caller_context("alice_near", 0));
+        contract.upvote(1);
+        contract.upvote(2);
+        assert_eq!(vec![1, 0, 2], ids(contract.get_ranked_posts(Ranking::Top, None, 1, 10)));
+
+        // a newer post outranks older ones with a lower score in the hot ranking
+        let mut context = get_caller_context("alice_near", 0);
+        context.block_timestamp = 100_000_000_000_000;
+        testing_env!(context);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+        assert_eq!(3, contract.get_ranked_posts(Ranking::Hot, None, 1, 1)[0].get_post_id());
+        assert_eq!(vec![3], ids(contract.get_ranked_posts(Ranking::Top, Some(1_000_000_000), 1, 10)));
+        // a window covering every post ranks them like the whole index
+        assert_eq!(vec![1, 0, 3, 2], ids(contract.get_ranked_posts(Ranking::Top, Some(100_000_000_000_000), 1, 10)));
+        assert_eq!(vec![3, 2], ids(contract.get_ranked_posts(Ranking::Top, Some(100_000_000_000_000), 2, 2)));
+
+        contract.delete_post(3, None);
+        assert_eq!(3, contract.get_ranked_posts(Ranking::Top, None, 1, 10).len());
+        assert_eq!(vec![1, 0, 2], ids(contract.get_ranked_posts(Ranking::Top, Some(100_000_000_000_000), 1, 10)));
+    }
+
+
+    #[test]
+    fn comments_are_voted_and_reacted_on() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+        contract.create_comment(0, "This is a comment".to_string(), None);
+
+        testing_env!(get_caller_context("bob_near", 0));
+        contract.upvote_comment(0);
+        contract.react_to_comment(0, Reaction::Laugh);
+        testing_env!(get_caller_context("carol_near", 0));
+        contract.downvote_comment(0);
+        contract.react_to_comment(0, Reaction::Laugh);
+        contract.react_to_comment(0, Reaction::Love);
+
+        let comment = &contract.get_comments(0)[0];
+        assert_eq!((1, 1), (comment.get_upvote_count(), comment.get_downvote_count()));
+        assert_eq!(1, comment.get_reaction_count(Reaction::Laugh));
+        assert_eq!(1, comment.get_reaction_count(Reaction::Love));
+        assert_eq!(VoteStatus::Downvoted, contract.get_user_comment_vote_status(0, "carol_near".to_string()));
+        assert_eq!(Some(Reaction::Love), contract.get_user_comment_reaction(0, "carol_near".to_string()));
+
+        contract.remove_comment_vote(0);
+        contract.remove_comment_reaction(0);
+        let comment = contract.get_comment(0);
+        assert_eq!(0, comment.get_downvote_count());
+        assert_eq!(0, comment.get_reaction_count(Reaction::Love));
+        assert_eq!(None, contract.get_user_comment_reaction(0, "carol_near".to_string()));
+    }
+
+
+    #[test]
+    fn posts_are_reacted_on() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+
+        contract.set_post_reaction_kinds(vec!["like".to_string(), "fire".to_string()]);
+        assert_eq!(vec!["like".to_string(), "fire".to_string()], contract.get_post_reaction_kinds());
+
+        testing_env!(get_caller_context("bob_near", 0));
+        contract.react_to_post(0, "fire".to_string());
+        contract.react_to_post(1, "like".to_string());
+        testing_env!(get_caller_context("carol_near", 0));
+        contract.react_to_post(0, "like".to_string());
+        contract.react_to_post(0, "fire".to_string());
+
+        let tallies = contract.get_posts_reactions(vec![0, 1, 7]);
+        assert_eq!(2, tallies.len());
+        assert_eq!(Some(&2), tallies[&0].get("fire"));
+        assert_eq!(None, tallies[&0].get("like"));
+        assert_eq!(Some(&1), tallies[&1].get("like"));
+
+        let reactions = contract.get_user_posts_reactions(vec![0, 1], "bob_near".to_string());
+        assert_eq!("fire".to_string(), reactions[&0]);
+        assert_eq!("like".to_string(), reactions[&1]);
+
+        contract.remove_post_reaction(0);
+        assert_eq!(None, contract.get_user_post_reaction(0, "carol_near".to_string()));
+        assert_eq!(Some(&1), contract.get_post(0).unwrap().get_reaction_counts().get("fire"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Unknown reaction funny")]
+    fn react_with_unknown_kind() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+        contract.set_post_reaction_kinds(vec!["like".to_string()]);
+
+        contract.react_to_post(0, "funny".to_string());
+    }
+
+
+    #[test]
+    fn reported_content_is_hidden_and_reviewed() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+        contract.set_report_threshold(2);
+        contract.grant_role("carol_near".to_string().try_into().unwrap(), Role::Moderator);
+
+        testing_env!(get_caller_context("bob_near", 0));
+        let available = contract.storage_balance_of("bob_near".to_string().try_into().unwrap()).unwrap().available;
+        contract.report(ReportTarget::Post { post_id: 0 }, "Spam".to_string());
+        assert_eq!(2, contract.get_total_posts());
+
+        testing_env!(get_caller_context("carol_near", 0));
+        contract.report(ReportTarget::Post { post_id: 0 }, "Spam".to_string());
+        assert!(contract.get_post(0).unwrap().is_hidden());
+        assert_eq!(1, contract.get_total_posts());
+        assert!(get_logs().iter().any(|log| log.contains("content_hidden")));
+
+        let queue = contract.get_report_queue(None, 10);
+        assert_eq!(1, queue.len());
+        assert_eq!(2, queue[0].get_report_count());
+
+        // dismissing shows the post again and gives the reporters their storage back
+        contract.dismiss_report(ReportTarget::Post { post_id: 0 });
+        assert!(!contract.get_post(0).unwrap().is_hidden());
+        assert_eq!(2, contract.get_total_posts());
+        assert!(contract.get_report_queue(None, 10).is_empty());
+        assert_eq!(available, contract.storage_balance_of("bob_near".to_string().try_into().unwrap()).unwrap().available);
+
+        // resolving deletes the content
+        contract.report(ReportTarget::Post { post_id: 1 }, "Abuse".to_string());
+        contract.resolve_report(ReportTarget::Post { post_id: 1 }, Some("Abuse".to_string()));
+        assert!(contract.get_post(1).unwrap().is_deleted());
+        assert!(contract.get_report_case(ReportTarget::Post { post_id: 1 }).is_none());
+    }
+
+    #[test]
+    fn hidden_comments_are_redacted_in_threads() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+        contract.create_comment(0, "This is a comment".to_string(), None);
+        contract.create_comment(0, "This is a reply".to_string(), Some(0));
+        contract.set_report_threshold(1);
+        contract.grant_role("carol_near".to_string().try_into().unwrap(), Role::Moderator);
+
+        testing_env!(get_caller_context("bob_near", 0));
+        contract.report(ReportTarget::Comment { comment_id: 0 }, "Abuse".to_string());
+
+        let thread = contract.get_post_comment_threads(0, None, 10);
+        assert_eq!(2, thread.len());
+        assert!(thread[0].get_body().is_empty());
+        assert_eq!(1, contract.get_comments(0).len());
+
+        testing_env!(get_caller_context("carol_near", 0));
+        contract.restore_content(ReportTarget::Comment { comment_id: 0 });
+        assert_eq!(2, contract.get_comments(0).len());
+        assert!(contract.get_report_case(ReportTarget::Comment { comment_id: 0 }).is_some());
     }
 
-}
+    #[test]
+    #[should_panic(expected = "You already reported this content")]
+    fn report_twice() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
 
-/*
- * The rest of this file holds the inline tests for the code above
- * Learn more about Rust tests: https://doc.rust-lang.org/book/ch11-01-writing-tests.html
- *
- * To run from contract directory:
- * cargo test -- --nocapture
- *
- * From project root, to run in combination with frontend tests:
- * yarn test
- *
- */
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use near_sdk::{MockedBlockchain};
-    use near_sdk::{testing_env, VMContext};
+        testing_env!(get_caller_context("bob_near", 0));
+        contract.report(ReportTarget::Post { post_id: 0 }, "Spam".to_string());
+        contract.report(ReportTarget::Post { post_id: 0 }, "Spam".to_string());
+    }
 
-    // mock the context for testing, notice "signer_account_id" that was accessed above from env::
-    fn get_context(input: Vec<u8>, is_view: bool) -> VMContext {
-        VMContext {
-            current_account_id: "decentrablog.npmrunstart.testnet".to_string(),
-            signer_account_id: "alice_near".to_string(),
-            signer_account_pk: vec![0, 1, 2],
-            predecessor_account_id: "alice_near".to_string(),
-            input,
-            block_index: 0,
-            block_timestamp: 0,
-            account_balance: 1000000000000000000000000,
-            account_locked_balance: 0,
-            storage_usage: 0,
-            attached_deposit: 0,
-            prepaid_gas: 10u64.pow(18),
-            random_seed: vec![0, 1, 2],
-            is_view,
-            output_data_receivers: vec![],
-            epoch_height: 19,
-        }
+    #[test]
+    fn probation_holds_drafts_published_later() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+        contract.sanction_account("carol_near".to_string().try_into().unwrap(), SanctionKind::Probation, "New account".to_string(), None);
+
+        testing_env!(get_caller_context("carol_near", 0));
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, Some(PostStatus::Draft), None);
+        contract.publish_post(0, None);
+        assert!(contract.get_post(0).unwrap().is_hidden());
+        assert!(contract.get_posts_by_cursor(None, 10, None).is_empty());
+        assert_eq!(0, contract.get_total_posts());
+
+        // the post is listed once a moderator clears it
+        testing_env!(get_caller_context("alice_near", 0));
+        contract.dismiss_report(ReportTarget::Post { post_id: 0 });
+        assert_eq!(0, contract.get_posts_by_cursor(None, 10, None)[0].get_post_id());
     }
 
     #[test]
-    fn create_post() {
+    fn held_replies_join_their_thread_once_cleared() {
         let context = get_context(vec![], false);
         testing_env!(context);
         let mut contract = Blog::default();
-        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string());
+        register_accounts(&mut contract);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+        contract.create_comment(0, "This is a comment".to_string(), None);
+        contract.sanction_account("carol_near".to_string().try_into().unwrap(), SanctionKind::Probation, "New account".to_string(), None);
 
-        //log id
-        env::log(format!("Debug here {}", contract.get_post(0).unwrap().get_post_id()).as_bytes());
-        
-        assert_eq!(
-            "This is the title".to_string(),
-            contract.get_post(0).unwrap().get_title()
-        );
-        assert_eq!(
-            "Lets go Brandon!".to_string(),
-            contract.get_post(0).unwrap().get_body()
-        );
-        assert_eq!(1, contract.get_total_posts());
-        assert_eq!(0, contract.get_post(0).unwrap().get_post_id());
+        testing_env!(get_caller_context("carol_near", 0));
+        let reply_id = contract.create_comment(0, "This is a reply".to_string(), Some(0));
+        assert_eq!(1, contract.get_post_comment_threads(0, None, 10).len());
+        assert!(contract.get_comment(0).get_children().is_empty());
 
-        //test get_user_posts
-        let user_posts = contract.get_user_posts("alice_near".to_string());
-        assert_eq!(1, user_posts.len());
-        assert_eq!(0, user_posts[0].get_post_id());
+        testing_env!(get_caller_context("alice_near", 0));
+        contract.dismiss_report(ReportTarget::Comment { comment_id: reply_id });
+        assert_eq!(reply_id, contract.get_post_comment_threads(0, Some(0), 10)[0].get_comment_id());
     }
 
     #[test]
-    fn delete_a_post_then_add_two_posts() {
+    fn republishing_keeps_hidden_posts_unlisted() {
         let context = get_context(vec![], false);
         testing_env!(context);
         let mut contract = Blog::default();
-        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string());
-        contract.delete_post(0);
-        
-        assert_eq!(0, contract.get_total_posts(), "Total posts should be 0");
+        register_accounts(&mut contract);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), Some(vec!["near".to_string()]), None, None);
+        contract.set_report_threshold(1);
 
-        // add a post
-        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string());
-        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string());
-        assert_eq!(2, contract.get_total_posts());
+        testing_env!(get_caller_context("bob_near", 0));
+        contract.report(ReportTarget::Post { post_id: 0 }, "Spam".to_string());
+        assert!(contract.get_post(0).unwrap().is_hidden());
 
-        //next post id
-        assert_eq!(3, contract.get_next_post_id());
+        testing_env!(get_caller_context("alice_near", 0));
+        contract.unpublish_post(0);
+        contract.publish_post(0, None);
+        assert_eq!(PostStatus::Published, contract.get_post(0).unwrap().get_status());
+        assert_eq!(0, contract.get_total_posts());
+        assert!(contract.get_posts_by_cursor(None, 10, None).is_empty());
+        assert!(contract.get_ranked_posts(Ranking::Top, None, 1, 10).is_empty());
+        assert!(contract.get_top_tags(10).is_empty());
+
+        // dismissing the reports lists it again
+        contract.dismiss_report(ReportTarget::Post { post_id: 0 });
+        assert_eq!(1, contract.get_total_posts());
     }
 
+
     #[test]
-    fn return_owner_account_id() {
+    fn sanctions_restrict_accounts() {
         let context = get_context(vec![], false);
         testing_env!(context);
-        let contract = Blog::default();
-        assert_eq!(
-            "alice_near".to_string(),
-            contract.get_owner()
-        );
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+
+        contract.sanction_account("bob_near".to_string().try_into().unwrap(), SanctionKind::Mute, "Flooding".to_string(), Some(1000));
+        contract.sanction_account("carol_near".to_string().try_into().unwrap(), SanctionKind::Probation, "New account".to_string(), None);
+
+        assert_eq!(2, contract.get_sanctions(1, 10).len());
+        assert_eq!("Flooding".to_string(), contract.get_sanction("bob_near".to_string()).unwrap().get_reason());
+
+        // muted accounts can still post and vote
+        testing_env!(get_caller_context("bob_near", 0));
+        contract.upvote(0);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+
+        // content of accounts on probation waits for review
+        testing_env!(get_caller_context("carol_near", 0));
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+        contract.create_comment(0, "This is a comment".to_string(), None);
+        assert_eq!(2, contract.get_total_posts());
+        assert!(contract.get_comments(0).is_empty());
+        // cases are queued in the order they were opened
+        let queue = contract.get_report_queue(None, 10);
+        assert_eq!(vec![ReportTarget::Post { post_id: 2 }, ReportTarget::Comment { comment_id: 0 }], queue.iter().map(|case| case.get_target()).collect::<Vec<_>>());
+        let last = (queue[0].get_opened_at(), queue[0].get_target());
+        assert_eq!(ReportTarget::Comment { comment_id: 0 }, contract.get_report_queue(Some(last), 10)[0].get_target());
+
+        testing_env!(get_caller_context("alice_near", 0));
+        contract.dismiss_report(ReportTarget::Post { post_id: 2 });
+        assert_eq!(3, contract.get_total_posts());
+
+        // the mute expires
+        let mut context = get_caller_context("bob_near", 0);
+        context.block_timestamp = 1000;
+        testing_env!(context);
+        assert!(contract.get_sanction("bob_near".to_string()).is_none());
+        contract.create_comment(0, "This is a comment".to_string(), None);
+
+        testing_env!(get_caller_context("alice_near", 0));
+        contract.lift_sanction("carol_near".to_string().try_into().unwrap());
+        assert!(contract.get_sanctions(1, 10).is_empty());
     }
 
     #[test]
-    fn create_comment() {
+    #[should_panic(expected = "Account is muted")]
+    fn muted_account_cannot_comment() {
         let context = get_context(vec![], false);
         testing_env!(context);
         let mut contract = Blog::default();
+        register_accounts(&mut contract);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+        contract.sanction_account("bob_near".to_string().try_into().unwrap(), SanctionKind::Mute, "Flooding".to_string(), None);
 
-        // Create the first post
-        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string());
-        contract.create_comment(0, "This is the comment".to_string());
+        testing_env!(get_caller_context("bob_near", 0));
+        contract.create_comment(0, "This is a comment".to_string(), None);
+    }
 
-        assert_eq!(
-            "This is the comment".to_string(),
-            contract.get_comment(0).get_body()
-        );
-        assert_eq!(0, contract.get_comment(0).get_comment_id());
+    #[test]
+    #[should_panic(expected = "Account is banned")]
+    fn banned_account_cannot_vote() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+        contract.sanction_account("bob_near".to_string().try_into().unwrap(), SanctionKind::Ban, "Spam".to_string(), None);
 
-        contract.create_comment(0, "This is comment 2, id 1".to_string());
-        contract.create_comment(0, "This is comment 3, id 2".to_string());
+        testing_env!(get_caller_context("bob_near", 0));
+        contract.upvote(0);
+    }
 
-        // Check if the comments is there
-        assert_eq!(
-            "This is comment 2, id 1".to_string(),
-            contract.get_comment(1).get_body()
-        );
-        assert_eq!(
-            "This is comment 3, id 2".to_string(),
-            contract.get_comment(2).get_body()
-        );
+    #[test]
+    #[should_panic(expected = "Not allowed to sanction this account")]
+    fn moderator_cannot_sanction_admin() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+        contract.grant_role("bob_near".to_string().try_into().unwrap(), Role::Admin);
+        contract.grant_role("carol_near".to_string().try_into().unwrap(), Role::Moderator);
 
-        let comments = contract.get_post(0).unwrap().get_comments();
+        testing_env!(get_caller_context("carol_near", 0));
+        contract.sanction_account("bob_near".to_string().try_into().unwrap(), SanctionKind::Ban, "Spam".to_string(), None);
+    }
 
-        //assert size of comments
-        assert_eq!(3, comments.len(), "Comments size is not 3");
-        assert_eq!(3, contract.get_post_total_comments(0), "get_post_total_comments is not 3");
 
-        //Check comment string
-        assert_eq!(
-            "This is comment 2, id 1".to_string(),
-            contract.get_comment(1).get_body()
-        );
+    #[test]
+    fn rate_limit_quota_resets_after_window() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+        contract.set_rate_limit(RateLimitedAction::Post, Some(RateLimit { max_actions: 2, window: 1000 }));
+        assert!(contract.get_rate_limit_quota("alice_near".to_string(), RateLimitedAction::Post).is_none());
+
+        let mut context = get_caller_context("bob_near", 0);
+        context.block_timestamp = 100;
+        testing_env!(context);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
         assert_eq!(
-            "This is comment 3, id 2".to_string(),
-            contract.get_comment(2).get_body()
+            Some(Quota { remaining: 0, resets_at: Some(1100) }),
+            contract.get_rate_limit_quota("bob_near".to_string(), RateLimitedAction::Post)
         );
+
+        let mut context = get_caller_context("bob_near", 0);
+        context.block_timestamp = 1100;
+        testing_env!(context);
+        assert_eq!(2, contract.get_rate_limit_quota("bob_near".to_string(), RateLimitedAction::Post).unwrap().remaining);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+
+        testing_env!(get_caller_context("alice_near", 0));
+        contract.set_rate_limit(RateLimitedAction::Post, None);
+        assert!(contract.get_rate_limit_quota("bob_near".to_string(), RateLimitedAction::Post).is_none());
     }
 
     #[test]
-    fn upvote_test() {
+    #[should_panic(expected = "Window must be at most")]
+    fn rate_limit_window_is_bounded() {
         let context = get_context(vec![], false);
         testing_env!(context);
         let mut contract = Blog::default();
 
-        // Create the first post
-        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string());
+        contract.set_rate_limit(RateLimitedAction::Post, Some(RateLimit { max_actions: 1, window: u64::MAX }));
+    }
 
-        // Upvote the post
+    #[test]
+    #[should_panic(expected = "Rate limit reached: at most 1 votes every 60 seconds")]
+    fn vote_rate_limit() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+        contract.set_rate_limit(RateLimitedAction::Vote, Some(RateLimit { max_actions: 1, window: 60_000_000_000 }));
+
+        testing_env!(get_caller_context("bob_near", 0));
         contract.upvote(0);
+        contract.upvote(1);
+    }
 
-        // Check if the upvote is there
-        assert_eq!(
-            1,
-            contract.get_post(0).unwrap().get_upvotes().len()
-        );
 
-        // upvote 10 times 
-        for _ in 0..10 {
-            contract.upvote(0);
-        }
+    #[test]
+    #[should_panic(expected = "Title must be at most 10 characters long")]
+    fn config_limits_titles() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+        contract.grant_role("bob_near".to_string().try_into().unwrap(), Role::Admin);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
 
-        // downvote 5 times
-        for _ in 0..5 {
-            contract.downvote(0);
-        }
+        testing_env!(get_caller_context("bob_near", 0));
+        contract.set_config(Config { max_title_length: 10, ..contract.get_config() });
+        assert_eq!(10, contract.get_config().max_title_length);
 
-        // check statistic, 10 times of upvote is 1 in an account
-        // 5 times of downvote is the same
-        assert_eq!(
-            (0, 1),
-            contract.get_votes_statistics(0)
-        );
-        
+        testing_env!(get_caller_context("alice_near", 0));
+        contract.edit_post(0, "This is the title".to_string(), "Lets go Brandon!".to_string(), None, None);
     }
 
     #[test]
-    fn test_paging_post() {
+    #[should_panic(expected = "Only owner or admins can update the config")]
+    fn set_config_by_other_account() {
         let context = get_context(vec![], false);
         testing_env!(context);
         let mut contract = Blog::default();
+        register_accounts(&mut contract);
 
-        // Loop 100 post and create them
-        for i in 0..45 {
-            contract.create_post(format!("This is the title {}", i), format!("Lets go Brandon! {}", i));
-        }
+        testing_env!(get_caller_context("bob_near", 0));
+        contract.set_config(Config::default());
+    }
 
-        assert_eq!(45, contract.get_total_posts(), "Total post is not 45");
-        
-        // Check paging post call
-        // let posts = contract.get_paging_posts(1, 10);
-        // assert_eq!(10, posts.len(), "Paging post size is not 10");
-        // assert_eq!(0, posts[0].get_post_id(), "Paging post id is not 10");
-        // assert_eq!(9, posts[9].get_post_id(), "Paging post id is not 20");
+    #[test]
+    #[should_panic(expected = "Max comment depth must be greater than 0")]
+    fn config_keeps_room_for_comments() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
 
-        // Check paging post call
-        let posts = contract.get_paging_posts(5, 10);
+        contract.set_config(Config { max_comment_depth: 0, ..contract.get_config() });
+    }
 
-        assert_eq!(5, posts.len(), "Paging post size is not 5");
-        assert_eq!(40, posts[0].get_post_id(), "Paging post id is not 40");
-        assert_eq!(44, posts[4].get_post_id(), "Paging post id is not 44");
+    #[test]
+    #[should_panic(expected = "Max comment depth must be at most 10")]
+    fn config_bounds_comment_depth() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+
+        contract.set_config(Config { max_comment_depth: 11, ..contract.get_config() });
     }
 
     #[test]
-    fn test_donation() {
+    #[should_panic(expected = "Tags must be at least 3 characters long")]
+    fn config_limits_tags() {
         let context = get_context(vec![], false);
         testing_env!(context);
         let mut contract = Blog::default();
+        register_accounts(&mut contract);
 
-        // Create the first post
-        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string());
+        contract.set_config(Config { min_tag_length: 3, ..contract.get_config() });
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), Some(vec!["ai".to_string()]), None, None);
+    }
 
-        // Donate
-        contract.donate(0, 1000000, "Support Trump for the USA".to_string());
 
-        // Check if the donation is there
-        assert_eq!(
-            1,
-            contract.get_post(0).unwrap().get_total_donation()
-        );
+    #[test]
+    #[should_panic(expected = "Account is banned")]
+    fn banned_admin_cannot_pause() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+        contract.grant_role("bob_near".to_string().try_into().unwrap(), Role::Admin);
+        contract.sanction_account("bob_near".to_string().try_into().unwrap(), SanctionKind::Ban, "Abuse".to_string(), None);
+
+        testing_env!(get_caller_context("bob_near", 0));
+        contract.pause(Feature::Posting);
+    }
+
+    #[test]
+    fn pause_features_independently() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+        contract.grant_role("bob_near".to_string().try_into().unwrap(), Role::Admin);
+
+        testing_env!(get_caller_context("bob_near", 0));
+        contract.pause(Feature::Posting);
+        assert_eq!(vec![Feature::Posting], contract.get_paused_features());
+        assert!(get_logs().iter().any(|log| log.contains("feature_paused")));
+
+        // other features keep working
+        testing_env!(get_caller_context("carol_near", 0));
+        contract.upvote(0);
+        contract.create_comment(0, "This is a comment".to_string(), None);
+
+        testing_env!(get_caller_context("alice_near", 0));
+        contract.unpause(Feature::Posting);
+        assert!(contract.get_paused_features().is_empty());
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Voting is paused")]
+    fn paused_voting() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+        contract.pause(Feature::Voting);
+
+        testing_env!(get_caller_context("bob_near", 0));
+        contract.react_to_post(0, "like".to_string());
+    }
+
+
+    #[test]
+    fn off_chain_post() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+        let hash = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08".to_string();
+        let off_chain = OffChainContent::new("ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi".to_string(), hash.clone(), 4);
+        contract.create_post("This is the title".to_string(), String::new(), None, None, Some(off_chain.clone()));
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, None);
+
+        let post = contract.get_post(0).unwrap();
+        assert_eq!(PostContent::OffChain(off_chain.clone()), post.get_content());
+        let json = serde_json::to_string(&post).unwrap();
+        assert!(json.contains(r#""content":{"mode":"off_chain","uri":"ipfs://"#));
+        assert!(serde_json::to_string(&contract.get_post(1).unwrap()).unwrap().contains(r#""content":{"mode":"on_chain"}"#));
+
+        // moving the body on-chain keeps the off-chain version as a revision
+        contract.edit_post(0, "This is the title".to_string(), "Lets go Brandon!".to_string(), None, None);
+        assert_eq!(PostContent::OnChain, contract.get_post(0).unwrap().get_content());
+        assert_eq!(PostContent::OffChain(off_chain), contract.get_post_revision(0, 0).unwrap().get_content());
+    }
+
+    #[test]
+    #[should_panic(expected = "Off-chain posts can't have an on-chain body")]
+    fn off_chain_post_with_body() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+        let off_chain = OffChainContent::new("ar://abc".to_string(), "0".repeat(64), 4);
+        contract.create_post("This is the title".to_string(), "Lets go Brandon!".to_string(), None, None, Some(off_chain));
+    }
+
+    #[test]
+    #[should_panic(expected = "Content hash must be a lowercase hex encoded SHA-256")]
+    fn off_chain_post_with_invalid_hash() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = Blog::default();
+        register_accounts(&mut contract);
+        let off_chain: OffChainContent = serde_json::from_str(r#"{"uri": "ar://abc", "sha256": "not a hash", "length": 4}"#).unwrap();
+        contract.create_post("This is the title".to_string(), String::new(), None, None, Some(off_chain));
     }
+
 }
\ No newline at end of file
@@ -0,0 +1,27 @@
+use near_sdk::serde::{Serialize, Deserialize};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+/// Areas of the blog admins can pause independently during an incident or a migration.
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone, Copy, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(rename_all = "snake_case")]
+pub enum Feature {
+    /// Creating, editing, publishing and unpublishing posts.
+    Posting,
+    Commenting,
+    /// Votes and reactions on posts and comments.
+    Voting,
+    /// NEAR and fungible token donations.
+    Donations,
+}
+
+impl Feature {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Feature::Posting => "Posting",
+            Feature::Commenting => "Commenting",
+            Feature::Voting => "Voting",
+            Feature::Donations => "Donations",
+        }
+    }
+}
@@ -1,9 +1,18 @@
-use std::collections::HashSet;
+use std::collections::HashMap;
 
 use near_sdk::{serde::{Serialize, Deserialize}, AccountId};
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 
-use crate::{PostId, donation::DonationLog};
+use crate::{PostId, VoteStatus, content::PostContent, donation::DonationLog, legacy::PostV1, tombstone::Tombstone};
+
+/// Drafts are only listed to their author, scheduled posts show up once `publish_at` has passed.
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone, Copy, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum PostStatus {
+    Draft,
+    Scheduled { publish_at: u64 },
+    Published,
+}
 
 /// Implements both `serde` and `borsh` serialization.
 /// `serde` is typically useful when returning a struct in JSON format for a frontend.
@@ -13,71 +22,197 @@ pub struct Post {
     post_id: usize,
     title: String,
     body: String,
+    content: PostContent,
+    tags: Vec<String>,
     author: AccountId,
     created_at: u64,
+    updated_at: u64,
     comments: Vec<usize>,
 
-    upvotes: HashSet<AccountId>,
-    downvotes: HashSet<AccountId>,
+    /// Voters are kept in `Blog::post_votes`, only the counts live on the post.
+    upvote_count: u64,
+    downvote_count: u64,
+    /// Reactors are kept in `Blog::post_reactions`.
+    reaction_counts: HashMap<String, u64>,
     
     donation_logs: Vec<DonationLog>,
+
+    /// Drafts, posts scheduled in the future and deleted posts are left out of every listing.
+    status: PostStatus,
+    tombstone: Option<Tombstone>,
+    /// Hidden after being reported too many times, until a moderator restores it.
+    hidden: bool,
+}
+
+impl From<PostV1> for Post {
+    fn from(post: PostV1) -> Self {
+        Self {
+            post_id: post.post_id,
+            title: post.title,
+            body: post.body,
+            content: PostContent::OnChain,
+            tags: Vec::new(),
+            author: post.author,
+            created_at: post.created_at,
+            updated_at: post.created_at,
+            comments: post.comments,
+
+            upvote_count: post.upvotes.len() as u64,
+            downvote_count: post.downvotes.len() as u64,
+            reaction_counts: HashMap::new(),
+
+            donation_logs: post.donation_logs.into_iter().map(DonationLog::from).collect(),
+
+            status: PostStatus::Published,
+            tombstone: None,
+            hidden: false,
+        }
+    }
 }
 
 impl Post {
-    pub fn new(post_id: usize, title: String, body: String, author: AccountId, created_at: u64) -> Self {
+    pub fn new(post_id: usize, title: String, body: String, content: PostContent, tags: Vec<String>, author: AccountId, created_at: u64, status: PostStatus) -> Self {
         Self {
             post_id,
             title,
             body,
+            content,
+            tags,
             author,
             created_at,
+            updated_at: created_at,
             comments: Vec::new(),
 
-            upvotes: HashSet::new(),
-            downvotes: HashSet::new(),
+            upvote_count: 0,
+            downvote_count: 0,
+            reaction_counts: HashMap::new(),
 
             donation_logs: Vec::new(),
+
+            status,
+            tombstone: None,
+            hidden: false,
         }
     }
     
+    pub fn edit(&mut self, title: String, body: String, content: PostContent, tags: Vec<String>, updated_at: u64) {
+        self.title = title;
+        self.body = body;
+        self.content = content;
+        self.tags = tags;
+        self.updated_at = updated_at;
+    }
+
+    /// Drops the content of the post, the tombstone records who deleted it.
+    pub fn delete(&mut self, tombstone: Tombstone) {
+        self.title = String::new();
+        self.body = String::new();
+        self.content = PostContent::OnChain;
+        self.tags = Vec::new();
+        self.tombstone = Some(tombstone);
+    }
+
+    pub fn is_deleted(&self) -> bool {
+        self.tombstone.is_some()
+    }
+
+    pub fn get_tombstone(&self) -> Option<Tombstone> {
+        self.tombstone.clone()
+    }
+
+    pub fn set_status(&mut self, status: PostStatus) {
+        self.status = status;
+    }
+
+    pub fn get_status(&self) -> PostStatus {
+        self.status
+    }
+
+    pub fn set_hidden(&mut self, hidden: bool) {
+        self.hidden = hidden;
+    }
+
+    pub fn is_hidden(&self) -> bool {
+        self.hidden
+    }
+
+    /// Whether the post belongs in the post and tag indexes, scheduled posts are indexed ahead of time.
+    pub fn is_indexed(&self) -> bool {
+        self.status != PostStatus::Draft && !self.is_deleted() && !self.hidden
+    }
+
+    /// Whether the post shows up in listings at `timestamp`.
+    pub fn is_visible(&self, timestamp: u64) -> bool {
+        let released = match self.status {
+            PostStatus::Draft => false,
+            PostStatus::Scheduled { publish_at } => publish_at <= timestamp,
+            PostStatus::Published => true,
+        };
+
+        released && !self.is_deleted() && !self.hidden
+    }
+
     pub fn add_comment(&mut self, comment_id: usize) {
         self.comments.push(comment_id);
     }
 
-    pub fn add_upvote(&mut self, account_id: AccountId) -> bool {
-        //check if there is downvote, if so remove it
-        if self.downvotes.contains(&account_id) {
-            self.downvotes.retain(|x| *x != account_id);
+    /// Keeps the vote counters in sync when an account changes its vote from `previous` to `next`.
+    pub fn update_vote_counts(&mut self, previous: &VoteStatus, next: &VoteStatus) {
+        match previous {
+            VoteStatus::Upvoted => self.upvote_count -= 1,
+            VoteStatus::Downvoted => self.downvote_count -= 1,
+            VoteStatus::None => {},
         }
 
-        self.upvotes.insert(account_id)
+        match next {
+            VoteStatus::Upvoted => self.upvote_count += 1,
+            VoteStatus::Downvoted => self.downvote_count += 1,
+            VoteStatus::None => {},
+        }
     }
 
-    pub fn remove_upvote(&mut self, account_id: AccountId) -> bool {
-        self.upvotes.remove(&account_id)
-    }
+    /// Keeps the reaction counters in sync when an account changes its reaction from `previous` to `next`.
+    pub fn update_reaction_counts(&mut self, previous: Option<String>, next: Option<String>) {
+        if let Some(previous) = previous {
+            let count = self.reaction_counts.get(&previous).copied().unwrap_or(0);
 
-    pub fn add_downvote(&mut self, account_id: AccountId) -> bool {
-        //check if there is upvote, if so remove it
-        if self.upvotes.contains(&account_id) {
-            self.upvotes.retain(|x| *x != account_id);
+            if count <= 1 {
+                self.reaction_counts.remove(&previous);
+            } else {
+                self.reaction_counts.insert(previous, count - 1);
+            }
         }
 
-        self.downvotes.insert(account_id)
+        if let Some(next) = next {
+            *self.reaction_counts.entry(next).or_insert(0) += 1;
+        }
     }
 
-    pub fn remove_downvote(&mut self, account_id: AccountId) -> bool {
-        self.downvotes.remove(&account_id)
+    pub fn get_reaction_counts(&self) -> HashMap<String, u64> {
+        self.reaction_counts.clone()
     }
 
-    pub fn get_points(&self) -> usize {
-        self.upvotes.len() - self.downvotes.len()
+    pub fn get_upvote_count(&self) -> u64 {
+        self.upvote_count
+    }
+
+    pub fn get_downvote_count(&self) -> u64 {
+        self.downvote_count
+    }
+
+    /// Upvotes minus downvotes, negative when a post is mostly downvoted.
+    pub fn get_score(&self) -> i64 {
+        self.upvote_count as i64 - self.downvote_count as i64
     }
 
     pub fn get_title(&self) -> String {
         self.title.clone()
     }
 
+    pub fn get_tags(&self) -> Vec<String> {
+        self.tags.clone()
+    }
+
     pub fn get_post_id(&self) -> PostId {
         self.post_id
     }
@@ -86,36 +221,32 @@ impl Post {
         self.author.clone()
     }
 
-    pub fn get_comments(&self) -> Vec<usize> {
-        self.comments.clone()
+    pub fn get_created_at(&self) -> u64 {
+        self.created_at
     }
 
-    pub fn remove_comment(&mut self, comment_id: usize) -> bool {
-        //return false if comment_id is not found
-        if let Some(index) = self.comments.iter().position(|&x| x == comment_id) {
-            self.comments.remove(index);
-            return true;
-        }
-        false
-    }
-
-    pub fn add_donation_logs(&mut self, donation_log: DonationLog) {
-        self.donation_logs.push(donation_log);
+    pub fn get_updated_at(&self) -> u64 {
+        self.updated_at
     }
 
-    pub fn get_upvotes(&self) -> HashSet<AccountId> {
-        self.upvotes.clone()
+    pub fn get_comments(&self) -> Vec<usize> {
+        self.comments.clone()
     }
 
-    pub fn get_downvotes(&self) -> HashSet<AccountId> {
-        self.downvotes.clone()
+    pub fn add_donation_logs(&mut self, donation_log: DonationLog) {
+        self.donation_logs.push(donation_log);
     }
 
-    pub fn get_total_donation(&self) -> u128 {
-        self.donation_logs.iter().map(|x| x.get_amount()).sum()
+    /// Sums the donations made in `token_id`, or in NEAR when it is `None`.
+    pub fn get_total_donation(&self, token_id: &Option<AccountId>) -> u128 {
+        self.donation_logs.iter().filter(|x| x.get_token_id() == *token_id).map(|x| x.get_amount()).sum()
     }
 
     pub fn get_body(&self) -> String {
         self.body.clone()
     }
+
+    pub fn get_content(&self) -> PostContent {
+        self.content.clone()
+    }
 }
@@ -0,0 +1,70 @@
+use near_sdk::serde::{Serialize, Deserialize};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+pub const MAX_DISPLAY_NAME_LENGTH: usize = 64;
+pub const MAX_BIO_LENGTH: usize = 280;
+pub const MAX_URL_LENGTH: usize = 256;
+pub const MAX_LINKS_PER_PROFILE: usize = 5;
+/// Most accounts a single `get_profiles` call resolves.
+pub const MAX_PROFILES_PER_CALL: usize = 100;
+
+/// What an account shows next to its posts and comments. Lengths are counted in bytes.
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Profile {
+    display_name: String,
+    bio: String,
+    /// URL or IPFS CID of the avatar image.
+    avatar: Option<String>,
+    links: Vec<String>,
+    updated_at: u64,
+}
+
+impl Profile {
+    pub fn new(display_name: String, bio: String, avatar: Option<String>, links: Vec<String>, updated_at: u64) -> Self {
+        let display_name = display_name.trim().to_string();
+
+        assert!(!display_name.is_empty(), "Display name can't be empty");
+        assert!(display_name.len() <= MAX_DISPLAY_NAME_LENGTH, "Display name must be at most {} bytes long", MAX_DISPLAY_NAME_LENGTH);
+        assert!(bio.len() <= MAX_BIO_LENGTH, "Bio must be at most {} bytes long", MAX_BIO_LENGTH);
+        assert!(
+            avatar.as_ref().is_none_or(|avatar| !avatar.is_empty() && avatar.len() <= MAX_URL_LENGTH),
+            "Avatar must be between 1 and {} bytes long",
+            MAX_URL_LENGTH
+        );
+        assert!(links.len() <= MAX_LINKS_PER_PROFILE, "A profile can have at most {} links", MAX_LINKS_PER_PROFILE);
+        assert!(
+            links.iter().all(|link| !link.is_empty() && link.len() <= MAX_URL_LENGTH),
+            "Links must be between 1 and {} bytes long",
+            MAX_URL_LENGTH
+        );
+
+        Self {
+            display_name,
+            bio,
+            avatar,
+            links,
+            updated_at,
+        }
+    }
+
+    pub fn get_display_name(&self) -> String {
+        self.display_name.clone()
+    }
+
+    pub fn get_bio(&self) -> String {
+        self.bio.clone()
+    }
+
+    pub fn get_avatar(&self) -> Option<String> {
+        self.avatar.clone()
+    }
+
+    pub fn get_links(&self) -> Vec<String> {
+        self.links.clone()
+    }
+
+    pub fn get_updated_at(&self) -> u64 {
+        self.updated_at
+    }
+}
@@ -0,0 +1,49 @@
+use near_sdk::serde::{Serialize, Deserialize};
+
+/// Seconds of age worth a tenfold score in the hot ranking, about 12.5 hours.
+const HOT_DECAY_SECONDS: f64 = 45000.0;
+/// Fractional ranks are stored as integers scaled by this factor.
+const RANK_SCALE: f64 = 1_000_000.0;
+
+/// Orders offered by `Blog::get_ranked_posts`. Every rank only depends on the votes of a post and on
+/// when it was published, so it is updated on votes and kept in an index instead of computed on read.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum Ranking {
+    /// Highest score first.
+    Top,
+    /// Score decayed by age like Hacker News, measured against the publication time rather than the
+    /// current time: a post needs ten times the score of one published 12.5 hours later to rank above it.
+    Hot,
+    /// Many votes split evenly between up and down first.
+    Controversial,
+}
+
+impl Ranking {
+    pub const ALL: [Ranking; 3] = [Ranking::Top, Ranking::Hot, Ranking::Controversial];
+
+    /// `published_at` is in nanoseconds, like `env::block_timestamp`.
+    pub fn rank(&self, upvotes: u64, downvotes: u64, published_at: u64) -> i64 {
+        let score = upvotes as i64 - downvotes as i64;
+
+        match self {
+            Ranking::Top => score,
+            Ranking::Hot => {
+                let order = (score.unsigned_abs().max(1) as f64).log10();
+                let seconds = (published_at / 1_000_000_000) as f64;
+
+                ((score.signum() as f64 * order + seconds / HOT_DECAY_SECONDS) * RANK_SCALE).round() as i64
+            },
+            Ranking::Controversial => {
+                if upvotes == 0 || downvotes == 0 {
+                    return 0;
+                }
+
+                let magnitude = (upvotes + downvotes) as f64;
+                let balance = upvotes.min(downvotes) as f64 / upvotes.max(downvotes) as f64;
+
+                (magnitude.powf(balance) * RANK_SCALE).round() as i64
+            },
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use near_sdk::serde::{Serialize, Deserialize};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+const MINUTE: u64 = 60_000_000_000;
+const HOUR: u64 = 60 * MINUTE;
+/// Longest window a limit can have, 30 days.
+pub const MAX_RATE_LIMIT_WINDOW: u64 = 30 * 24 * HOUR;
+
+/// Actions an account can only take a limited number of times per window.
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone, Copy, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitedAction {
+    Post,
+    Comment,
+    /// Votes on posts and comments, removing a vote counts too.
+    Vote,
+}
+
+impl RateLimitedAction {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            RateLimitedAction::Post => "posts",
+            RateLimitedAction::Comment => "comments",
+            RateLimitedAction::Vote => "votes",
+        }
+    }
+}
+
+/// At most `max_actions` in any `window` nanoseconds.
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone, Copy, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RateLimit {
+    pub max_actions: u32,
+    pub window: u64,
+}
+
+impl RateLimit {
+    pub fn assert_valid(&self) {
+        assert!(self.max_actions > 0, "Max actions must be greater than 0");
+        assert!(self.window > 0, "Window must be greater than 0");
+        assert!(self.window <= MAX_RATE_LIMIT_WINDOW, "Window must be at most {} nanoseconds", MAX_RATE_LIMIT_WINDOW);
+    }
+
+    /// Drops the timestamps that left the window ending at `now`.
+    pub fn prune(&self, log: &mut Vec<u64>, now: u64) {
+        log.retain(|timestamp| timestamp.saturating_add(self.window) > now);
+    }
+
+    pub fn get_quota(&self, log: &[u64]) -> Quota {
+        Quota {
+            remaining: self.max_actions.saturating_sub(log.len() as u32),
+            resets_at: log.first().map(|timestamp| timestamp.saturating_add(self.window)),
+        }
+    }
+}
+
+/// What an account can still do in the current window.
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Quota {
+    pub remaining: u32,
+    /// When the oldest action in the window stops counting.
+    pub resets_at: Option<u64>,
+}
+
+/// The limit of each action, `None` leaves it unlimited.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct RateLimits {
+    post: Option<RateLimit>,
+    comment: Option<RateLimit>,
+    vote: Option<RateLimit>,
+}
+
+impl Default for RateLimits {
+    fn default() -> Self {
+        Self {
+            post: Some(RateLimit { max_actions: 5, window: HOUR }),
+            comment: Some(RateLimit { max_actions: 10, window: MINUTE }),
+            vote: Some(RateLimit { max_actions: 30, window: MINUTE }),
+        }
+    }
+}
+
+impl RateLimits {
+    pub fn get(&self, action: RateLimitedAction) -> Option<RateLimit> {
+        match action {
+            RateLimitedAction::Post => self.post,
+            RateLimitedAction::Comment => self.comment,
+            RateLimitedAction::Vote => self.vote,
+        }
+    }
+
+    pub fn set(&mut self, action: RateLimitedAction, limit: Option<RateLimit>) {
+        if let Some(limit) = limit.as_ref() {
+            limit.assert_valid();
+        }
+
+        match action {
+            RateLimitedAction::Post => self.post = limit,
+            RateLimitedAction::Comment => self.comment = limit,
+            RateLimitedAction::Vote => self.vote = limit,
+        }
+    }
+}
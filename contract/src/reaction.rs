@@ -0,0 +1,48 @@
+use near_sdk::serde::{Serialize, Deserialize};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+pub const MAX_POST_REACTION_KINDS: usize = 10;
+pub const MAX_POST_REACTION_KIND_LENGTH: usize = 32;
+
+/// Reactions offered on posts until the owner configures their own.
+pub fn default_post_reaction_kinds() -> Vec<String> {
+    vec!["like", "insightful", "funny", "confused"].into_iter().map(String::from).collect()
+}
+
+/// Post reaction kinds are short names like `insightful`, the frontend maps them to emojis.
+pub fn assert_valid_post_reaction_kinds(kinds: &[String]) {
+    assert!(!kinds.is_empty(), "At least one reaction kind is required");
+    assert!(kinds.len() <= MAX_POST_REACTION_KINDS, "At most {} reaction kinds can be configured", MAX_POST_REACTION_KINDS);
+
+    for (index, kind) in kinds.iter().enumerate() {
+        assert!(
+            !kind.is_empty() && kind.len() <= MAX_POST_REACTION_KIND_LENGTH,
+            "Reaction kinds must be between 1 and {} characters long",
+            MAX_POST_REACTION_KIND_LENGTH
+        );
+        assert!(
+            kind.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_'),
+            "Reaction kinds can only contain lowercase letters, digits and underscores"
+        );
+        assert!(!kinds[..index].contains(kind), "Duplicate reaction kind {}", kind);
+    }
+}
+
+/// Emoji reactions an account can leave on a comment, one at a time.
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(rename_all = "snake_case")]
+pub enum Reaction {
+    /// 👍
+    Like,
+    /// ❤️
+    Love,
+    /// 😂
+    Laugh,
+    /// 😮
+    Wow,
+    /// 😢
+    Sad,
+    /// 😡
+    Angry,
+}
@@ -0,0 +1,115 @@
+use near_sdk::{serde::{Serialize, Deserialize}, AccountId, StorageUsage};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+use crate::{CommentId, PostId};
+
+pub const MAX_REPORT_REASON_LENGTH: usize = 280;
+/// Reports needed to hide a post or comment until a moderator reviews it, until the owner sets another threshold.
+pub const DEFAULT_REPORT_THRESHOLD: u64 = 3;
+
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ReportTarget {
+    Post { post_id: PostId },
+    Comment { comment_id: CommentId },
+}
+
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Report {
+    reporter: AccountId,
+    reason: String,
+    created_at: u64,
+    /// Charged to the reporter, given back when the case is closed.
+    #[serde(skip)]
+    storage_bytes: StorageUsage,
+}
+
+impl Report {
+    pub fn new(reporter: AccountId, reason: String, created_at: u64) -> Self {
+        assert!(!reason.is_empty(), "Reason can't be empty");
+        assert!(reason.len() <= MAX_REPORT_REASON_LENGTH, "Reason must be at most {} bytes long", MAX_REPORT_REASON_LENGTH);
+
+        Self {
+            reporter,
+            reason,
+            created_at,
+            storage_bytes: 0,
+        }
+    }
+
+    pub fn get_reporter(&self) -> AccountId {
+        self.reporter.clone()
+    }
+
+    pub fn get_reason(&self) -> String {
+        self.reason.clone()
+    }
+
+    pub fn get_storage_bytes(&self) -> StorageUsage {
+        self.storage_bytes
+    }
+}
+
+/// The open reports against a post or comment, waiting in the moderation queue.
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ReportCase {
+    target: ReportTarget,
+    reports: Vec<Report>,
+    opened_at: u64,
+    /// Whether the target was hidden after reaching the report threshold.
+    hidden: bool,
+}
+
+impl ReportCase {
+    pub fn new(target: ReportTarget, opened_at: u64) -> Self {
+        Self {
+            target,
+            reports: Vec::new(),
+            opened_at,
+            hidden: false,
+        }
+    }
+
+    pub fn add_report(&mut self, report: Report) {
+        assert!(
+            !self.reports.iter().any(|existing| existing.reporter == report.reporter),
+            "You already reported this content"
+        );
+
+        self.reports.push(report);
+    }
+
+    /// Records what the last report cost its reporter, the `u64` keeps the size of the case unchanged.
+    pub fn set_last_report_storage(&mut self, storage_bytes: StorageUsage) {
+        if let Some(report) = self.reports.last_mut() {
+            report.storage_bytes = storage_bytes;
+        }
+    }
+
+    pub fn get_target(&self) -> ReportTarget {
+        self.target.clone()
+    }
+
+    pub fn get_opened_at(&self) -> u64 {
+        self.opened_at
+    }
+
+    pub fn get_reports(&self) -> &Vec<Report> {
+        &self.reports
+    }
+
+    pub fn get_report_count(&self) -> u64 {
+        self.reports.len() as u64
+    }
+
+    pub fn set_hidden(&mut self, hidden: bool) {
+        self.hidden = hidden;
+    }
+
+    pub fn is_hidden(&self) -> bool {
+        self.hidden
+    }
+}
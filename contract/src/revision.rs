@@ -0,0 +1,65 @@
+use near_sdk::{serde::{Serialize, Deserialize}, AccountId};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+use crate::content::PostContent;
+
+/// Revisions kept for a post, an edit past this drops the oldest one.
+pub const MAX_REVISIONS_PER_POST: usize = 50;
+/// Most revisions a single `get_post_revisions` call returns.
+pub const MAX_REVISIONS_PER_CALL: usize = 10;
+
+/// A previous version of a post, kept when its author edits it.
+/// `editor` wrote this version at `created_at`, the edit replacing it is the next revision or the post itself.
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Revision {
+    revision_id: usize,
+    title: String,
+    body: String,
+    content: PostContent,
+    tags: Vec<String>,
+    editor: AccountId,
+    created_at: u64,
+}
+
+impl Revision {
+    pub fn new(revision_id: usize, title: String, body: String, content: PostContent, tags: Vec<String>, editor: AccountId, created_at: u64) -> Self {
+        Self {
+            revision_id,
+            title,
+            body,
+            content,
+            tags,
+            editor,
+            created_at,
+        }
+    }
+
+    pub fn get_revision_id(&self) -> usize {
+        self.revision_id
+    }
+
+    pub fn get_title(&self) -> String {
+        self.title.clone()
+    }
+
+    pub fn get_body(&self) -> String {
+        self.body.clone()
+    }
+
+    pub fn get_content(&self) -> PostContent {
+        self.content.clone()
+    }
+
+    pub fn get_tags(&self) -> Vec<String> {
+        self.tags.clone()
+    }
+
+    pub fn get_editor(&self) -> AccountId {
+        self.editor.clone()
+    }
+
+    pub fn get_created_at(&self) -> u64 {
+        self.created_at
+    }
+}
@@ -0,0 +1,32 @@
+use near_sdk::serde::{Serialize, Deserialize};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+/// Roles are ordered by privilege: each role can do everything the roles below it can.
+/// `Owner` is never stored, it always belongs to `Blog::owner`.
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone, Copy, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum Role {
+    Owner,
+    Admin,
+    Moderator,
+}
+
+impl Role {
+    fn rank(&self) -> u8 {
+        match self {
+            Role::Owner => 2,
+            Role::Admin => 1,
+            Role::Moderator => 0,
+        }
+    }
+
+    /// Whether holding `self` grants the permissions of `role`.
+    pub fn includes(&self, role: Role) -> bool {
+        self.rank() >= role.rank()
+    }
+
+    /// Whether an account holding `self` may grant or revoke `role`.
+    pub fn outranks(&self, role: Role) -> bool {
+        self.rank() > role.rank()
+    }
+}
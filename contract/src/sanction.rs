@@ -0,0 +1,63 @@
+use near_sdk::{serde::{Serialize, Deserialize}, AccountId};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+pub const MAX_SANCTION_REASON_LENGTH: usize = 280;
+
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone, Copy, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(rename_all = "snake_case")]
+pub enum SanctionKind {
+    /// Can't call any method that changes state, except to manage its storage deposit.
+    Ban,
+    /// Can't comment.
+    Mute,
+    /// New posts and comments are hidden in the moderation queue until a moderator dismisses their case.
+    Probation,
+}
+
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Sanction {
+    kind: SanctionKind,
+    reason: String,
+    issued_by: AccountId,
+    issued_at: u64,
+    /// `None` for a permanent sanction.
+    expires_at: Option<u64>,
+}
+
+impl Sanction {
+    pub fn new(kind: SanctionKind, reason: String, issued_by: AccountId, issued_at: u64, expires_at: Option<u64>) -> Self {
+        assert!(!reason.is_empty(), "Reason can't be empty");
+        assert!(reason.len() <= MAX_SANCTION_REASON_LENGTH, "Reason must be at most {} bytes long", MAX_SANCTION_REASON_LENGTH);
+        assert!(expires_at.is_none_or(|expires_at| expires_at > issued_at), "Expiry must be in the future");
+
+        Self {
+            kind,
+            reason,
+            issued_by,
+            issued_at,
+            expires_at,
+        }
+    }
+
+    pub fn get_kind(&self) -> SanctionKind {
+        self.kind
+    }
+
+    pub fn get_reason(&self) -> String {
+        self.reason.clone()
+    }
+
+    pub fn get_issued_by(&self) -> AccountId {
+        self.issued_by.clone()
+    }
+
+    pub fn get_expires_at(&self) -> Option<u64> {
+        self.expires_at
+    }
+
+    pub fn is_active(&self, timestamp: u64) -> bool {
+        self.expires_at.is_none_or(|expires_at| timestamp < expires_at)
+    }
+}
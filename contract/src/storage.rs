@@ -0,0 +1,85 @@
+use near_sdk::{Balance, StorageUsage};
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Serialize, Deserialize};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+/// Upper bound of the bytes taken by a storage registration of the longest possible account id.
+pub const STORAGE_REGISTRATION_BYTES: StorageUsage = 160;
+
+/// NEP-145 balance of an account.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StorageBalance {
+    pub total: U128,
+    pub available: U128,
+}
+
+/// NEP-145 bounds of a storage balance, there is no maximum.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StorageBalanceBounds {
+    pub min: U128,
+    pub max: Option<U128>,
+}
+
+/// What an account deposited for storage and how many bytes its posts, comments and votes use.
+/// The registration itself is covered by the minimum balance and not counted in `used_bytes`.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct StorageAccount {
+    deposit: Balance,
+    used_bytes: StorageUsage,
+    /// Bytes of content kept from an earlier registration closed with `force`, the deposit it left behind pays for them.
+    orphaned_bytes: StorageUsage,
+}
+
+impl StorageAccount {
+    pub fn new(deposit: Balance) -> Self {
+        Self {
+            deposit,
+            used_bytes: 0,
+            orphaned_bytes: 0,
+        }
+    }
+
+    pub fn get_deposit(&self) -> Balance {
+        self.deposit
+    }
+
+    pub fn get_used_bytes(&self) -> StorageUsage {
+        self.used_bytes
+    }
+
+    pub fn get_orphaned_bytes(&self) -> StorageUsage {
+        self.orphaned_bytes
+    }
+
+    pub fn set_orphaned_bytes(&mut self, bytes: StorageUsage) {
+        self.orphaned_bytes = bytes;
+    }
+
+    pub fn deposit(&mut self, amount: Balance) {
+        self.deposit += amount;
+    }
+
+    pub fn withdraw(&mut self, amount: Balance) {
+        self.deposit -= amount;
+    }
+
+    pub fn use_bytes(&mut self, bytes: StorageUsage) {
+        self.used_bytes += bytes;
+    }
+
+    /// Bytes freed from content of an earlier registration are not credited, they were paid for already.
+    pub fn free_bytes(&mut self, bytes: StorageUsage) {
+        let orphaned = bytes.min(self.orphaned_bytes);
+        self.orphaned_bytes -= orphaned;
+
+        // content written before the account registered was never charged
+        self.used_bytes = self.used_bytes.saturating_sub(bytes - orphaned);
+    }
+
+    /// The part of the deposit not locked by the registration and the used bytes.
+    pub fn get_available(&self, min_balance: Balance, byte_cost: Balance) -> Balance {
+        self.deposit.saturating_sub(min_balance + Balance::from(self.used_bytes) * byte_cost)
+    }
+}
@@ -0,0 +1,27 @@
+use crate::config::Config;
+
+/// Lowercases tags and joins their words with `-`, so `Rust Lang` and `rust-lang` are the same tag.
+/// Duplicates are dropped, the order given by the author is kept.
+pub fn normalize_tags(tags: Vec<String>, config: &Config) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+
+    for tag in tags {
+        let tag = tag.split_whitespace().collect::<Vec<&str>>().join("-").to_lowercase();
+
+        assert!(!tag.is_empty(), "Tags can't be empty");
+        assert!(tag.len() >= config.min_tag_length, "Tags must be at least {} characters long", config.min_tag_length);
+        assert!(tag.len() <= config.max_tag_length, "Tags must be at most {} characters long", config.max_tag_length);
+        assert!(
+            tag.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'),
+            "Tags can only contain letters, digits and dashes"
+        );
+
+        if !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+
+    assert!(normalized.len() <= config.max_tags_per_post, "A post can have at most {} tags", config.max_tags_per_post);
+
+    normalized
+}
@@ -0,0 +1,39 @@
+use near_sdk::{serde::{Serialize, Deserialize}, AccountId};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+pub const MAX_DELETE_REASON_LENGTH: usize = 280;
+
+/// Left in place of a deleted post or comment, so its id still resolves.
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Tombstone {
+    deleted_by: AccountId,
+    deleted_at: u64,
+    reason: Option<String>,
+}
+
+impl Tombstone {
+    pub fn new(deleted_by: AccountId, deleted_at: u64, reason: Option<String>) -> Self {
+        if let Some(reason) = reason.as_ref() {
+            assert!(reason.len() <= MAX_DELETE_REASON_LENGTH, "Reason must be at most {} bytes long", MAX_DELETE_REASON_LENGTH);
+        }
+
+        Self {
+            deleted_by,
+            deleted_at,
+            reason,
+        }
+    }
+
+    pub fn get_deleted_by(&self) -> AccountId {
+        self.deleted_by.clone()
+    }
+
+    pub fn get_deleted_at(&self) -> u64 {
+        self.deleted_at
+    }
+
+    pub fn get_reason(&self) -> Option<String> {
+        self.reason.clone()
+    }
+}
@@ -5,6 +5,7 @@ import { HashLoader } from "react-spinners";
 import { useNavigate } from "react-router-dom";
 import useQuery from "../hooks/useQuery";
 import { getTransactionUrl } from "../utils/near";
+import { ensureStorageRegistered } from "../utils";
 
 export default function CreateNewPost() {
   //get url params
@@ -31,6 +32,7 @@ export default function CreateNewPost() {
 
     try {
       setCreatingPost(true);
+      await ensureStorageRegistered();
 
       const result = window.contract.create_post({
         title,
@@ -8,6 +8,7 @@ import { toast } from "react-toastify";
 import { ThumbDownIcon, ThumbUpIcon } from "@heroicons/react/solid";
 import { Helmet } from "react-helmet";
 import moment from "moment";
+import { ensureStorageRegistered } from "../../utils";
 
 export default function PostView() {
   const { id } = useParams();
@@ -80,6 +81,7 @@ export default function PostView() {
 
   const handleUpvoteButton = async () => {
     try {
+      await ensureStorageRegistered();
       const result =
         voteStatus === "Upvoted"
           ? window.contract.remove_upvote({
@@ -119,6 +121,7 @@ export default function PostView() {
 
   const handleDownvoteButton = async () => {
     try {
+      await ensureStorageRegistered();
       const result =
         voteStatus === "Downvoted"
           ? window.contract.remove_downvote({
@@ -241,6 +244,7 @@ function CommentForm({ post_id }) {
     const post_id = e.target.elements.post_id.value;
 
     try {
+      await ensureStorageRegistered();
       const result = window.contract.create_comment({
         body,
         post_id: parseInt(post_id),
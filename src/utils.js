@@ -1,8 +1,11 @@
-import { connect, Contract, keyStores, WalletConnection } from "near-api-js";
+import { connect, Contract, keyStores, utils, WalletConnection } from "near-api-js";
 import getConfig from "./config";
 
 const nearConfig = getConfig(process.env.NODE_ENV || "development");
 
+// Deposited on top of the registration minimum to pay for the first posts, comments and votes
+const STORAGE_ALLOWANCE = "0.1";
+
 // Initialize contract & set global variables
 export async function initContract() {
   // Initialize connection to the NEAR testnet
@@ -31,6 +34,35 @@ export async function initContract() {
         "get_post",
         "get_owner",
         "get_paging_posts",
+        "get_posts_by_cursor",
+        "get_posts_by_tag",
+        "get_top_tags",
+        "get_user_drafts",
+        "get_profile",
+        "get_profiles",
+        "is_following",
+        "get_follow_statistics",
+        "get_followers",
+        "get_following",
+        "get_feed",
+        "get_ranked_posts",
+        "get_user_comment_vote_status",
+        "get_user_comment_reaction",
+        "get_user_post_reaction",
+        "get_posts_reactions",
+        "get_user_posts_reactions",
+        "get_post_reaction_kinds",
+        "get_report_queue",
+        "get_report_case",
+        "get_report_threshold",
+        "get_sanction",
+        "get_sanctions",
+        "get_rate_limit",
+        "get_rate_limit_quota",
+        "get_config",
+        "get_paused_features",
+        "get_proposed_owner",
+        "is_migrating",
         "get_total_posts",
         "get_comments",
         "get_paging_comments",
@@ -41,18 +73,62 @@ export async function initContract() {
         "get_next_post_id",
         "get_user_posts",
         "get_user_vote_status",
+        "get_post_revisions",
+        "get_post_revision",
+        "get_post_comment_threads",
+        "get_comment_thread",
+        "get_post_total_donation",
+        "get_role",
+        "get_role_members",
+        "storage_balance_of",
+        "storage_balance_bounds",
       ],
       // Change methods can modify the state. But you don't receive the returned value when called.
       changeMethods: [
         "create_post",
+        "edit_post",
         "create_comment",
         "delete_comment",
         "delete_post",
+        "unpublish_post",
+        "publish_post",
         "donate",
         "upvote",
         "remove_upvote",
         "downvote",
         "remove_downvote",
+        "upvote_comment",
+        "downvote_comment",
+        "remove_comment_vote",
+        "react_to_comment",
+        "remove_comment_reaction",
+        "react_to_post",
+        "remove_post_reaction",
+        "set_post_reaction_kinds",
+        "report",
+        "resolve_report",
+        "dismiss_report",
+        "restore_content",
+        "set_report_threshold",
+        "sanction_account",
+        "lift_sanction",
+        "set_rate_limit",
+        "set_config",
+        "pause",
+        "unpause",
+        "migrate_batch",
+        "propose_owner",
+        "accept_ownership",
+        "cancel_owner_proposal",
+        "grant_role",
+        "revoke_role",
+        "storage_deposit",
+        "storage_withdraw",
+        "storage_unregister",
+        "set_profile",
+        "remove_profile",
+        "follow",
+        "unfollow",
       ],
     }
   );
@@ -71,3 +147,23 @@ export function login() {
   // the private key in localStorage.
   window.walletConnection.requestSignIn(nearConfig.contractName);
 }
+
+// Posts, comments and votes are paid for by a storage deposit (NEP-145), so an
+// account is registered before its first write. Registering goes through the
+// wallet, the action is sent again once the user is back on the page.
+export async function ensureStorageRegistered() {
+  const balance = await window.contract.storage_balance_of({
+    account_id: window.accountId,
+  });
+  if (balance) {
+    return;
+  }
+
+  const { min } = await window.contract.storage_balance_bounds();
+  const allowance = utils.format.parseNearAmount(STORAGE_ALLOWANCE);
+
+  await window.contract.storage_deposit({
+    args: {},
+    amount: (BigInt(min) + BigInt(allowance)).toString(),
+  });
+}